@@ -0,0 +1,72 @@
+//! Lexer throughput on synthetic multi-megabyte sources, reported in MB/s.
+//!
+//! Run with `cargo bench -p exodusc_lexer`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use exodusc_lexer::{scanner::Token, Lexer, TokenStream};
+
+const TARGET_SIZE: usize = 4 * 1024 * 1024;
+
+const FUNCTIONS: &str = include_str!("../tests/corpus/functions.ex");
+const CONTROL_FLOW: &str = include_str!("../tests/corpus/control_flow.ex");
+const STRINGS: &str = include_str!("../tests/corpus/strings.ex");
+
+/// Identifiers, keywords, operators and numbers, with little trivia.
+const DENSE: &str = "func f(a: i32, b: i32): i32 { let c = a * 2 + b / 3 - 0x1F; return c % 7; }\n";
+
+/// Repeats `unit` until the source is at least [`TARGET_SIZE`] bytes long.
+fn synthetic(unit: &str) -> String {
+    unit.repeat(TARGET_SIZE.div_ceil(unit.len()))
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+    vec![
+        ("code", synthetic(&[FUNCTIONS, CONTROL_FLOW].concat())),
+        ("strings", synthetic(STRINGS)),
+        ("dense", synthetic(DENSE)),
+    ]
+}
+
+fn tokenization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokenization");
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, source| {
+            b.iter(|| Lexer::tokenization("bench", black_box(source)).tokens().len())
+        });
+    }
+    group.finish();
+}
+
+fn stream(c: &mut Criterion) {
+    let mut group = c.benchmark_group("stream");
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, source| {
+            b.iter(|| TokenStream::new(black_box(source)).count())
+        });
+    }
+    group.finish();
+}
+
+/// Lexing plus walking every significant token, as the parser does.
+fn next_token(c: &mut Criterion) {
+    let mut group = c.benchmark_group("next_token");
+    for (name, source) in inputs() {
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &source, |b, source| {
+            b.iter(|| {
+                let mut lexer = Lexer::tokenization("bench", black_box(source));
+                let mut count = 0usize;
+                while lexer.next_token() != Token::EOF {
+                    count += 1;
+                }
+                count
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tokenization, stream, next_token);
+criterion_main!(benches);
@@ -0,0 +1,112 @@
+use crate::span::Span;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum LexicalErrors {
+    LEXICAL_INVALID_CHARACTER,
+    LEXICAL_UNTERMINATED_COMMENT,
+    LEXICAL_INVALID_ESCAPE,
+    LEXICAL_UNTERMINATED_STRING,
+    LEXICAL_UNTERMINATED_CHAR,
+    LEXICAL_EMPTY_CHAR,
+    LEXICAL_MULTIPLE_CHARS,
+    LEXICAL_INVALID_NUMBER,
+    LEXICAL_INVALID_DIGIT,
+    LEXICAL_INVALID_SUFFIX,
+    LEXICAL_MISSING_DIGITS,
+    LEXICAL_NUMBER_OVERFLOW,
+}
+
+impl LexicalErrors {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexicalErrors::LEXICAL_INVALID_CHARACTER => "E0001",
+            LexicalErrors::LEXICAL_UNTERMINATED_COMMENT => "E0002",
+            LexicalErrors::LEXICAL_INVALID_ESCAPE => "E0003",
+            LexicalErrors::LEXICAL_UNTERMINATED_STRING => "E0004",
+            LexicalErrors::LEXICAL_UNTERMINATED_CHAR => "E0005",
+            LexicalErrors::LEXICAL_EMPTY_CHAR => "E0006",
+            LexicalErrors::LEXICAL_MULTIPLE_CHARS => "E0007",
+            LexicalErrors::LEXICAL_INVALID_NUMBER => "E0008",
+            LexicalErrors::LEXICAL_INVALID_DIGIT => "E0009",
+            LexicalErrors::LEXICAL_INVALID_SUFFIX => "E0010",
+            LexicalErrors::LEXICAL_MISSING_DIGITS => "E0011",
+            LexicalErrors::LEXICAL_NUMBER_OVERFLOW => "E0012",
+        }
+    }
+}
+
+impl std::fmt::Display for LexicalErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexicalErrors::LEXICAL_INVALID_CHARACTER => write!(f, "invalid character"),
+            LexicalErrors::LEXICAL_UNTERMINATED_COMMENT => write!(f, "unterminated block comment"),
+            LexicalErrors::LEXICAL_INVALID_ESCAPE => write!(f, "invalid escape sequence"),
+            LexicalErrors::LEXICAL_UNTERMINATED_STRING => write!(f, "unterminated string literal"),
+            LexicalErrors::LEXICAL_UNTERMINATED_CHAR => write!(f, "unterminated character literal"),
+            LexicalErrors::LEXICAL_EMPTY_CHAR => write!(f, "empty character literal"),
+            LexicalErrors::LEXICAL_MULTIPLE_CHARS => {
+                write!(f, "character literal may only contain one character")
+            }
+            LexicalErrors::LEXICAL_INVALID_NUMBER => write!(f, "invalid numeric literal"),
+            LexicalErrors::LEXICAL_INVALID_DIGIT => write!(f, "invalid digit for the literal's radix"),
+            LexicalErrors::LEXICAL_INVALID_SUFFIX => write!(f, "invalid suffix for numeric literal"),
+            LexicalErrors::LEXICAL_MISSING_DIGITS => write!(f, "numeric literal has no digits"),
+            LexicalErrors::LEXICAL_NUMBER_OVERFLOW => write!(f, "numeric literal does not fit its type"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexError {
+    pub kind: LexicalErrors,
+    pub span: Span,
+}
+
+impl LexError {
+    pub fn new(kind: LexicalErrors, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
+
+/// Suspicious but valid source, reported without stopping compilation.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[allow(non_camel_case_types)]
+pub enum LexicalWarnings {
+    LEXICAL_BIDI_CHARACTER,
+    LEXICAL_CONFUSABLE_IDENTIFIER,
+}
+
+impl LexicalWarnings {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexicalWarnings::LEXICAL_BIDI_CHARACTER => "W0001",
+            LexicalWarnings::LEXICAL_CONFUSABLE_IDENTIFIER => "W0002",
+        }
+    }
+}
+
+impl std::fmt::Display for LexicalWarnings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexicalWarnings::LEXICAL_BIDI_CHARACTER => {
+                write!(f, "bidirectional control character may display code differently than it runs")
+            }
+            LexicalWarnings::LEXICAL_CONFUSABLE_IDENTIFIER => {
+                write!(f, "identifier contains characters that look like ASCII letters")
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexWarning {
+    pub kind: LexicalWarnings,
+    pub span: Span,
+}
+
+impl LexWarning {
+    pub fn new(kind: LexicalWarnings, span: Span) -> Self {
+        Self { kind, span }
+    }
+}
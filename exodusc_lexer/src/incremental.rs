@@ -0,0 +1,134 @@
+use crate::{
+    errors::LexError,
+    scanner::{StringPart, Token},
+    source_map::LineIndex,
+    span::Span,
+    unicode, Lexer, Tokenizer,
+};
+use std::{borrow::Cow, ops::Range};
+
+/// Replaces the bytes in `range` of the current source with `new_text`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TextEdit {
+    pub range: Span,
+    pub new_text: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Span, new_text: impl Into<String>) -> Self {
+        Self { range, new_text: new_text.into() }
+    }
+}
+
+/// Token indices touched by [`Lexer::apply_edit`], as indices into
+/// [`Lexer::tokens`]: `removed` before the edit, `inserted` after it. Tokens
+/// outside those ranges are the same as before, shifted to their new offsets.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChangedTokens {
+    pub removed: Range<usize>,
+    pub inserted: Range<usize>,
+}
+
+impl<'src> Lexer<'src> {
+    /// Applies `edit` to the source and re-lexes only the region it affects.
+    /// The rest of the source is lexed first, as by [`Lexer::tokens`].
+    ///
+    /// Lexing restarts at the last whitespace before the edit that lies
+    /// outside any interpolated string, and stops as soon as a new token
+    /// lines up with an old one past the edit, from where the old tokens are
+    /// reused. The cursor goes back to the start of the source.
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds or not on character boundaries.
+    pub fn apply_edit(&mut self, edit: &TextEdit) -> ChangedTokens {
+        self.fill_all();
+        let Span { start, end } = edit.range;
+        let first = self.restart_index(edit);
+        let restart = first.checked_sub(1).map_or(0, |last| self.data[last].1.end);
+
+        let mut source = std::mem::take(&mut self.source).into_owned();
+        source.replace_range(start..end, &edit.new_text);
+        let inserted_end = start + edit.new_text.len();
+        let shift = |offset: usize| offset + inserted_end - end;
+        let unshift = |offset: usize| offset + end - inserted_end;
+
+        let mut tokenizer = Tokenizer::starting_at(&source, restart);
+        let mut tokens = Vec::new();
+        let mut old = first;
+        let mut old_depth = 0usize;
+        let mut resync = None;
+        loop {
+            let nested = tokenizer.is_nested();
+            let Some((token, span)) = tokenizer.next() else { break };
+            if span.start >= inserted_end && !nested {
+                let old_start = unshift(span.start);
+                while old < self.data.len() && self.data[old].1.start < old_start {
+                    match self.data[old].0 {
+                        Token::Interpolation(StringPart::Head) => old_depth += 1,
+                        Token::Interpolation(StringPart::Tail) => old_depth = old_depth.saturating_sub(1),
+                        _ => (),
+                    }
+                    old += 1;
+                }
+                // The rest of the source is unchanged, so from a matching
+                // boundary in the same mode the old tokens are still right.
+                if old_depth == 0 && self.data.get(old) == Some(&(token, Span::new(old_start, unshift(span.end)))) {
+                    resync = Some((old, span.start));
+                    break;
+                }
+            }
+            tokens.push((token, span));
+        }
+
+        let (last, resumed) = resync.unwrap_or((self.data.len(), source.len()));
+        let old_resumed = self.data.get(last).map_or(unshift(source.len()), |(_, span)| span.start);
+        let relexed = tokenizer.finish().into_iter().filter(|error| error.span.start < resumed);
+        let mut errors: Vec<LexError> = Vec::with_capacity(self.errors.len());
+        errors.extend(self.errors.iter().filter(|error| error.span.start < restart).cloned());
+        errors.extend(relexed);
+        errors.extend(self.errors.iter().filter(|error| error.span.start >= old_resumed).map(|error| {
+            LexError::new(error.kind, Span::new(shift(error.span.start), shift(error.span.end)))
+        }));
+
+        for (_, span) in &mut self.data[last..] {
+            *span = Span::new(shift(span.start), shift(span.end));
+        }
+        let inserted = first..first + tokens.len();
+        self.data.splice(first..last, tokens);
+        self.errors = errors;
+        self.warnings = unicode::check(&self.data, &source);
+        self.lines = LineIndex::new(&source);
+        self.source = Cow::Owned(source);
+        self.reset();
+
+        ChangedTokens { removed: first..last, inserted }
+    }
+
+    /// Index of the first token to re-lex for `edit`: the one after the last
+    /// whitespace ending at or before its start, outside any interpolated
+    /// string. Nothing before that whitespace can merge with the edited text.
+    fn restart_index(&self, edit: &TextEdit) -> usize {
+        let offset = edit.range.start;
+        let next = edit.new_text.chars().next().or_else(|| self.source[edit.range.end..].chars().next());
+        let joins_space = matches!(next, Some(' ' | '\t' | '\x0C'));
+        let mut restart = 0;
+        let mut depth = 0usize;
+        for (i, (token, span)) in self.data.iter().enumerate() {
+            if span.end > offset {
+                break;
+            }
+            match token {
+                Token::Interpolation(StringPart::Head) => depth += 1,
+                Token::Interpolation(StringPart::Tail) => depth = depth.saturating_sub(1),
+                // A `\r` right before the edit could still turn into `\r\n`,
+                // and whitespace into a longer run.
+                Token::Line if span.end == offset && self.source[..span.end].ends_with('\r') => (),
+                Token::Space if span.end == offset && joins_space => (),
+                Token::Space | Token::Line if depth == 0 => restart = i + 1,
+                _ => (),
+            }
+        }
+        restart
+    }
+}
@@ -1,148 +1,517 @@
+use errors::{LexError, LexWarning};
+use literals::Segment;
 use logos::Logos;
-use scanner::Token;
+use scanner::{StringPart, Token};
+use source_map::{ColumnUnit, LineIndex, Location};
+use std::borrow::Cow;
+use span::Span;
 
+pub mod errors;
+pub mod incremental;
+mod literals;
 pub mod scanner;
+pub mod source_map;
+pub mod span;
+pub mod symbol;
+pub mod token_set;
+pub mod trivia;
 pub mod types;
+pub mod unicode;
+
+pub use literals::text;
 
 #[cfg(test)]
 mod tests;
 
-pub struct Lexer {
-    filename: String,
-    source: String,
-    data: Vec<Token>,
-    line: usize,
-    cursor: usize,
-    idx: i32,
+/// Saved lexer position, see [`Lexer::checkpoint`].
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Checkpoint {
+    idx: usize,
+    current: Span,
+    previous: Span,
+}
+
+/// Tokens lexed ahead of the cursor at a time, and consumed tokens kept
+/// behind it so short rewinds need no re-lexing.
+const CHUNK: usize = 512;
+
+/// Token stream over a borrowed source. Identifiers are interned
+/// [`symbol::Symbol`]s, and strings and doc comments carry no text at all,
+/// it is decoded from the source when asked for, see [`Lexer::text`]. So
+/// tokens are `Copy` and nothing is cloned while parsing. The source is only
+/// copied once an edit is applied, see [`Lexer::apply_edit`].
+///
+/// Tokens are lexed a chunk at a time as the cursor reaches them, and the
+/// ones it left far behind are dropped, so parsing holds a window of tokens
+/// rather than all of them. The cursor always rests on the next significant
+/// token, so peeking is an index, and lines are only looked up in the
+/// [`LineIndex`] when asked for.
+pub struct Lexer<'src> {
+    filename: &'src str,
+    source: Cow<'src, str>,
+    /// Lexes the rest of the source; `None` once all of it has been lexed.
+    tokenizer: Option<Tokenizer<'src>>,
+    /// Tokens lexed so far, the first one being the `base`-th of the source.
+    data: Vec<(Token, Span)>,
+    base: usize,
+    /// Tokens lexed and tokenizer errors recorded so far, so that lexing
+    /// dropped tokens again does not record their diagnostics twice.
+    lexed: usize,
+    reported: usize,
+    errors: Vec<LexError>,
+    warnings: Vec<LexWarning>,
+    lines: LineIndex,
+    /// Index of the next significant token among all tokens of the source,
+    /// or their number.
+    idx: usize,
+    current: Span,
+    previous: Span,
 }
 
-impl Lexer {
-    pub fn tokenization(filename: &str, source: &str) -> Self {
-        let tokens = scanner::Token::lexer(&source);
-        let mut buff = Vec::new();
-        for token in tokens {
-            buff.push(token);
-        }
-        Self {
-            filename: filename.to_string(),
-            source: source.to_string(),
-            data: buff,
-            line: 1,
-            cursor: 0,
+impl<'src> Lexer<'src> {
+    pub fn tokenization(filename: &'src str, source: &'src str) -> Self {
+        let mut lexer = Self {
+            filename,
+            source: Cow::Borrowed(source),
+            tokenizer: Some(Tokenizer::new(source)),
+            data: Vec::with_capacity(2 * CHUNK),
+            base: 0,
+            lexed: 0,
+            reported: 0,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            lines: LineIndex::new(source),
             idx: 0,
-        }
+            current: Span::default(),
+            previous: Span::default(),
+        };
+        lexer.skip_trivia();
+        lexer
     }
 
     pub fn reset(&mut self) {
-        self.line = 1;
-        self.idx = 0;
-    }
-
-    pub fn next(&mut self) -> Token {
-        while self.idx < self.data.len() as i32 {
-            let token = &self.data[self.idx as usize];
-            self.idx += 1;
-            self.cursor += token.to_string().len();
-            match token {
-                Token::Line => {
-                    self.line += 1;
-                    self.cursor = 0;
-                    continue;
+        self.rewind(Checkpoint::default());
+    }
+
+    /// Returns the next significant token, or [`Token::EOF`] once the
+    /// source is exhausted.
+    #[inline]
+    pub fn next_token(&mut self) -> Token {
+        self.previous = self.current;
+        match self.data.get(self.idx - self.base) {
+            Some(&(token, span)) => {
+                self.idx += 1;
+                self.skip_trivia();
+                self.current = span;
+                token
+            }
+            None => {
+                self.current = Span::new(self.source.len(), self.source.len());
+                Token::EOF
+            }
+        }
+    }
+
+    /// Moves the cursor past trivia and stray characters, which have already
+    /// been reported, lexing more of the source when it runs out of tokens.
+    #[inline]
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.data.get(self.idx - self.base) {
+                Some((token, _)) if is_significant(token) => return,
+                Some(_) => self.idx += 1,
+                None => {
+                    self.drop_consumed();
+                    if !self.fill() {
+                        return;
+                    }
                 }
-                Token::Space | Token::Error => continue,
-                _ => return token.clone(),
             }
         }
-        Token::EOF
     }
 
+    /// Drops the tokens more than [`CHUNK`] behind the cursor, unless every
+    /// token is to be kept.
+    fn drop_consumed(&mut self) {
+        let consumed = self.idx - self.base;
+        if self.tokenizer.is_some() && consumed > CHUNK {
+            self.data.drain(..consumed - CHUNK);
+            self.base += consumed - CHUNK;
+        }
+    }
+
+    /// Lexes up to [`CHUNK`] more tokens and records their errors and
+    /// warnings. Returns `false` once the source is exhausted.
+    fn fill(&mut self) -> bool {
+        let Some(tokenizer) = &mut self.tokenizer else { return false };
+        let start = self.data.len();
+        self.data.extend(std::iter::from_fn(|| tokenizer.next()).take(CHUNK));
+
+        let end = self.base + self.data.len();
+        if end > self.lexed {
+            let first = self.lexed.max(self.base + start) - self.base;
+            self.warnings.extend(unicode::check(&self.data[first..], &self.source));
+            self.lexed = end;
+        }
+        let found = &tokenizer.tokens.extras.errors;
+        if found.len() > self.reported {
+            let first = self.errors.len();
+            self.errors.extend_from_slice(&found[self.reported..]);
+            self.errors[first..].sort_by_key(|error| error.span.start);
+            self.reported = found.len();
+        }
+        if self.data.len() - start < CHUNK {
+            // Only now can a string left open by a missing `}` be told apart.
+            self.errors = self.tokenizer.take().map_or_else(Vec::new, Tokenizer::finish);
+        }
+        self.data.len() > start
+    }
+
+    /// Lexes the rest of the source, keeping every token from the first one.
+    fn fill_all(&mut self) {
+        if self.base > 0 {
+            self.relex(0);
+        }
+        while self.fill() {}
+    }
+
+    /// Lexes the source again from the start up to the `idx`-th token, which
+    /// has been dropped already, and continues from there.
+    fn relex(&mut self, idx: usize) {
+        let Cow::Borrowed(source) = self.source else {
+            unreachable!("the tokens of an edited source are all kept")
+        };
+        let mut tokenizer = Tokenizer::new(source);
+        for _ in 0..idx {
+            tokenizer.next();
+        }
+        self.tokenizer = Some(tokenizer);
+        self.data.clear();
+        self.base = idx;
+    }
+
+    /// Span of the token most recently returned by [`Lexer::next_token`].
+    pub fn span(&self) -> Span {
+        self.current
+    }
+
+    /// Span of the token returned by [`Lexer::next_token`] before the current one.
+    pub fn last_span(&self) -> Span {
+        self.previous
+    }
+
+    /// Column, in characters, at which the current token starts.
     pub fn column(&self) -> usize {
-        self.cursor
+        self.location(self.current.start).1
+    }
+
+    /// Line (1-based) and column (0-based, in characters) of a byte offset.
+    pub fn location(&self, offset: usize) -> (usize, usize) {
+        let Location { line, column } = self.location_in(offset, ColumnUnit::Char);
+        (line, column)
+    }
+
+    /// Line and column of a byte offset, with the column counted in `unit`.
+    pub fn location_in(&self, offset: usize, unit: ColumnUnit) -> Location {
+        self.lines.location(&self.source, offset, unit)
     }
 
-    pub fn peek(&mut self) -> Token {
-        let peek = self.next();
-        self.back();
-        return peek;
+    pub fn line_index(&self) -> &LineIndex {
+        &self.lines
     }
 
-    pub fn skip(&mut self) {
-        if self.idx >= self.data.len() as i32 {
-            return;
+    /// Next significant token, without consuming it.
+    #[inline]
+    pub fn peek(&self) -> Token {
+        self.data.get(self.idx - self.base).map_or(Token::EOF, |&(token, _)| token)
+    }
+
+    /// The `n`-th significant token ahead (`0` is the next one), without
+    /// consuming anything. Returns [`Token::EOF`] past the end of the source.
+    pub fn peek_nth(&mut self, n: usize) -> Token {
+        let mut ahead = n;
+        let mut idx = self.idx;
+        loop {
+            if idx - self.base == self.data.len() && !self.fill() {
+                return Token::EOF;
+            }
+            let (token, _) = self.data[idx - self.base];
+            if is_significant(&token) {
+                if ahead == 0 {
+                    return token;
+                }
+                ahead -= 1;
+            }
+            idx += 1;
         }
-        self.idx += 1;
     }
 
-    pub fn skip_line(&mut self) {
-        let line = self.line;
-        while self.line == line {
-            self.next();
+    /// Span of the next significant token; empty at the end of the source.
+    pub fn peek_span(&self) -> Span {
+        let end = Span::new(self.source.len(), self.source.len());
+        self.data.get(self.idx - self.base).map_or(end, |&(_, span)| span)
+    }
+
+    /// Captures the current position so it can be restored with [`Lexer::rewind`].
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            idx: self.idx,
+            current: self.current,
+            previous: self.previous,
         }
-        self.back();
     }
 
-    pub fn next_identifier(&mut self) -> Option<String> {
-        match self.next() {
-            scanner::Token::Identifier(name) => Some(name.to_owned()),
-            _ => None,
+    /// Restores the exact position captured by [`Lexer::checkpoint`]. A
+    /// position whose tokens have been dropped is lexed again.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) {
+        if checkpoint.idx < self.base {
+            self.relex(checkpoint.idx);
         }
+        self.idx = checkpoint.idx;
+        self.current = checkpoint.current;
+        self.previous = checkpoint.previous;
+        self.skip_trivia();
     }
 
-    pub fn back(&mut self) {
-        while self.idx - 1 > 0 {
-            match self.data[(self.idx - 1) as usize].clone() {
-                Token::Line => {
-                    self.line -= 1;
-                    self.idx -= 1;
-                    self.cursor = 0;
-                }
-                Token::Space | Token::Error => {
-                    self.idx -= 1;
-                    self.cursor -= 1
-                }
-                token => {
-                    self.cursor -= token.to_string().len();
-                    self.idx -= 1;
-                    return;
-                }
+    pub fn skip_token(&mut self) {
+        if self.data.get(self.idx - self.base).is_some() {
+            self.next_token();
+        }
+    }
+
+    pub fn skip_line(&mut self) {
+        let line = self.line();
+        loop {
+            let checkpoint = self.checkpoint();
+            if self.next_token() == Token::EOF || self.line() != line {
+                self.rewind(checkpoint);
+                return;
             }
         }
     }
 
-    pub fn filename(&self) -> &str {
-        self.filename.as_ref()
+    pub fn next_identifier(&mut self) -> Option<symbol::Symbol> {
+        match self.next_token() {
+            scanner::Token::Identifier(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Every token with its surrounding whitespace, newlines and comments
+    /// attached, for tools that must reproduce the source exactly.
+    pub fn with_trivia(&mut self) -> Vec<trivia::TriviaToken> {
+        self.fill_all();
+        trivia::attach(&self.data, self.source.len())
     }
 
+    /// Lexical errors in the whole source, in source order. The rest of the
+    /// source is lexed first, see [`Lexer::errors_so_far`].
+    pub fn errors(&mut self) -> &[LexError] {
+        while self.fill() {}
+        &self.errors
+    }
+
+    /// Lexical errors in the tokens lexed so far, in source order. That is
+    /// at least every token up to the one [`Lexer::peek`] returns.
+    pub fn errors_so_far(&self) -> &[LexError] {
+        &self.errors
+    }
+
+    /// Bidirectional control characters and confusable identifiers in the
+    /// whole source, in source order. See [`unicode`].
+    pub fn warnings(&mut self) -> &[LexWarning] {
+        while self.fill() {}
+        &self.warnings
+    }
+
+    pub fn filename(&self) -> &'src str {
+        self.filename
+    }
+
+    /// Line (1-based) on which the current token ends.
     pub fn line(&self) -> usize {
-        self.line
+        self.lines.line(self.current.end)
     }
 
+    /// Text of a line (1-based), without its line terminator.
     pub fn get_source_line(&self, line_number: usize) -> Option<&str> {
-        let mut current_line = 1;
-        let mut start_idx = 0;
-        let mut end_idx = 0;
-        for (i, c) in self.source.char_indices() {
-            if current_line == line_number && (i == 0 || &self.source[i - 1..i] == "\n") {
-                start_idx = i;
-            }
-            if current_line == line_number && c == '\n' {
-                end_idx = i;
-                break;
+        self.lines.line_span(line_number).map(|span| &self.source[span])
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Text of the string literal, interpolated string part or doc comment
+    /// at `span`, decoded again on every call, see [`text`].
+    pub fn text(&self, span: Span) -> Cow<'_, str> {
+        text(&self.source[span.start..span.end])
+    }
+
+    /// Every token, trivia included, with its span. Indices into this slice
+    /// are what [`Lexer::apply_edit`] reports as changed. The rest of the
+    /// source is lexed first, and from then on every token is kept.
+    pub fn tokens(&mut self) -> &[(Token, Span)] {
+        self.fill_all();
+        &self.data
+    }
+}
+
+/// Whether the parser sees `token`: trivia is skipped, and so are stray
+/// characters, which have been reported already. Matched rather than
+/// compared, as the derived `PartialEq` is not inlined.
+#[inline]
+fn is_significant(token: &Token) -> bool {
+    !token.is_trivia() && !matches!(token, Token::Error)
+}
+
+/// An interpolated string whose embedded expression is being lexed.
+struct Interpolation {
+    /// Offset of the opening `"`.
+    start: usize,
+    /// Braces opened inside the expression and not closed yet.
+    depth: usize,
+}
+
+/// Drives the logos scanner. Interpolated strings are lexed in a nested
+/// mode: the `}` closing an embedded expression resumes the string instead
+/// of being emitted as a brace.
+///
+/// A leading byte order mark comes out as [`Token::Space`] and a `#!` line
+/// at the top of the file as [`Token::Comment`], so both are skipped like
+/// any other trivia.
+struct Tokenizer<'s> {
+    tokens: logos::Lexer<'s, Token>,
+    interpolations: Vec<Interpolation>,
+    /// BOM and shebang tokens still to be returned, last one first.
+    prelude: Vec<(Token, Span)>,
+}
+
+impl<'s> Tokenizer<'s> {
+    fn new(source: &'s str) -> Self {
+        Self::starting_at(source, 0)
+    }
+
+    /// Starts lexing at `offset`, which must be a token boundary outside any
+    /// interpolated string.
+    fn starting_at(source: &'s str, offset: usize) -> Self {
+        let mut prelude = Vec::new();
+        let bom = if source.starts_with('\u{FEFF}') { 3 } else { 0 };
+        if offset < bom {
+            prelude.push((Token::Space, Span::new(0, bom)));
+        }
+        if offset <= bom && source[bom..].starts_with("#!") {
+            let line = source[bom..].find(['\r', '\n']).unwrap_or(source.len() - bom);
+            prelude.push((Token::Comment, Span::new(bom, bom + line)));
+        }
+        let end = prelude.last().map_or(offset, |(_, span): &(Token, Span)| span.end);
+        prelude.reverse();
+
+        let mut tokens = Token::lexer(source);
+        tokens.bump(end);
+        Self { tokens, interpolations: Vec::new(), prelude }
+    }
+
+    /// Whether the next token is lexed inside an interpolated string.
+    fn is_nested(&self) -> bool {
+        !self.interpolations.is_empty()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<(Token, Span)> {
+        if let Some(token) = self.prelude.pop() {
+            return Some(token);
+        }
+        let reported = self.tokens.extras.errors.len();
+        let mut token = self.tokens.next()?;
+        match token {
+            Token::Error if std::mem::take(&mut self.tokens.extras.interpolation) => {
+                let start = self.tokens.span().start;
+                self.interpolations.push(Interpolation { start, depth: 0 });
+                token = Token::Interpolation(StringPart::Head);
             }
-            if c == '\n' {
-                current_line += 1;
+            Token::LBrace => {
+                if let Some(open) = self.interpolations.last_mut() {
+                    open.depth += 1;
+                }
             }
+            Token::RBrace => match self.interpolations.last_mut() {
+                Some(open) if open.depth > 0 => open.depth -= 1,
+                Some(_) => {
+                    token = match literals::segment(&mut self.tokens) {
+                        Segment::Open => Token::Interpolation(StringPart::Middle),
+                        Segment::Closed => Token::Interpolation(StringPart::Tail),
+                        Segment::Unterminated => Token::Error,
+                    };
+                    if token != Token::Interpolation(StringPart::Middle) {
+                        self.interpolations.pop();
+                    }
+                }
+                None => (),
+            },
+            _ => (),
         }
-        if end_idx == 0 && current_line == line_number {
-            end_idx = self.source.len();
+        let span = Span::from(self.tokens.span());
+        // Callbacks report their own failures; anything else is a stray character.
+        if token == Token::Error && self.tokens.extras.errors.len() == reported {
+            let error = LexError::new(errors::LexicalErrors::LEXICAL_INVALID_CHARACTER, span);
+            self.tokens.extras.errors.push(error);
         }
-        if start_idx == 0 && end_idx == 0 {
-            return None;
+        Some((token, span))
+    }
+
+    /// Errors found so far, in source order, including an interpolated
+    /// string still open at the end of the source.
+    fn finish(self) -> Vec<LexError> {
+        let len = self.tokens.source().len();
+        let mut errors = self.tokens.extras.errors;
+        // With a `}` missing, the closing `"` opens a string of its own that
+        // runs to the end as well; only the outermost string is reported.
+        if let Some(open) = self.interpolations.first() {
+            let unterminated = errors::LexicalErrors::LEXICAL_UNTERMINATED_STRING;
+            errors.retain(|error| error.kind != unterminated || error.span.start < open.start || error.span.end < len);
+            errors.push(LexError::new(unterminated, Span::new(open.start, len)));
         }
-        Some(&self.source[start_idx..end_idx])
+        errors.sort_by_key(|error| error.span.start);
+        errors
     }
+}
 
-    pub fn source(&self) -> &str {
-        self.source.as_ref()
+/// Tokens of a source lexed on demand, trivia included, without buffering
+/// them. For single-pass consumers such as highlighters; the parser needs
+/// the lookahead, rewinding and edits of a [`Lexer`] instead.
+pub struct TokenStream<'src> {
+    tokenizer: Tokenizer<'src>,
+}
+
+impl<'src> TokenStream<'src> {
+    pub fn new(source: &'src str) -> Self {
+        Self { tokenizer: Tokenizer::new(source) }
+    }
+
+    /// Lexical errors found so far, in source order; once the stream is
+    /// exhausted, every error in the source.
+    pub fn finish(self) -> Vec<LexError> {
+        self.tokenizer.finish()
+    }
+}
+
+impl Iterator for TokenStream<'_> {
+    type Item = (Token, Span);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Token, Span)> {
+        self.tokenizer.next()
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        match self.next_token() {
+            Token::EOF => None,
+            token => Some(token),
+        }
     }
 }
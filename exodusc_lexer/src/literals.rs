@@ -0,0 +1,436 @@
+use crate::{
+    errors::{LexError, LexicalErrors::{self, *}},
+    scanner::{Literal, Token},
+    span::Span,
+    types::Type,
+};
+use std::{borrow::Cow, ops::Range};
+
+/// Decodes the escape sequence at the start of `rest`, the text right after a `\`.
+/// Returns the decoded character, if valid, and how many bytes of `rest` it spans.
+fn escape(rest: &str) -> (Option<char>, usize) {
+    let mut chars = rest.chars();
+    let escaped = match chars.next() {
+        Some('n') => '\n',
+        Some('r') => '\r',
+        Some('t') => '\t',
+        Some('0') => '\0',
+        Some('\\') => '\\',
+        Some('"') => '"',
+        Some('\'') => '\'',
+        Some('x') => {
+            let digits = rest[1..].bytes().take(2).take_while(u8::is_ascii_hexdigit).count();
+            let value = match digits {
+                2 => u8::from_str_radix(&rest[1..3], 16).ok().filter(u8::is_ascii),
+                _ => None,
+            };
+            return (value.map(char::from), 1 + digits);
+        }
+        Some('u') => {
+            if !rest[1..].starts_with('{') {
+                return (None, 1);
+            }
+            let digits = rest[2..].bytes().take_while(u8::is_ascii_hexdigit).count();
+            if !rest[2 + digits..].starts_with('}') {
+                return (None, 2 + digits);
+            }
+            let value = match digits {
+                1..=6 => u32::from_str_radix(&rest[2..2 + digits], 16).ok().and_then(char::from_u32),
+                _ => None,
+            };
+            return (value, 3 + digits);
+        }
+        Some(other) => return (None, other.len_utf8()),
+        None => return (None, 0),
+    };
+    (Some(escaped), 1)
+}
+
+/// How a run of string text ended.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Segment {
+    /// At the closing `"`.
+    Closed,
+    /// At a `{` that opens an embedded expression.
+    Open,
+    Unterminated,
+}
+
+/// Skips string text right after the current token, up to and including the
+/// closing `"` or an interpolation `{`, and reports its invalid escapes.
+/// `{{` and `}}` stand for literal braces. The text itself is decoded only
+/// when asked for, see [`text`].
+pub(crate) fn segment(lex: &mut logos::Lexer<Token>) -> Segment {
+    let start = lex.span().start;
+    let base = lex.span().end;
+    let rest = lex.remainder();
+    let bytes = rest.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                lex.bump(i + 1);
+                return Segment::Closed;
+            }
+            b'{' | b'}' if bytes.get(i + 1) == Some(&bytes[i]) => i += 2,
+            b'{' => {
+                lex.bump(i + 1);
+                return Segment::Open;
+            }
+            b'\\' => {
+                let (escaped, len) = escape(&rest[i + 1..]);
+                if escaped.is_none() {
+                    let span = Span::new(base + i, base + i + 1 + len);
+                    lex.extras.errors.push(LexError::new(LEXICAL_INVALID_ESCAPE, span));
+                }
+                i += 1 + len;
+            }
+            _ => i += 1,
+        }
+    }
+    lex.bump(rest.len());
+    let span = Span::new(start, base + rest.len());
+    lex.extras.errors.push(LexError::new(LEXICAL_UNTERMINATED_STRING, span));
+    Segment::Unterminated
+}
+
+/// Reads the body of a `"..."` literal, the opening quote already matched.
+/// A string that opens an interpolation sets
+/// [`Extras::interpolation`](crate::scanner::Extras) for the lexer to pick up.
+pub(crate) fn string(lex: &mut logos::Lexer<Token>) -> Option<Literal> {
+    match segment(lex) {
+        Segment::Closed => Some(Literal::String),
+        Segment::Open => {
+            lex.extras.interpolation = true;
+            None
+        }
+        Segment::Unterminated => None,
+    }
+}
+
+/// Reads a raw string `r"..."` or `r#"..."#`, the opening delimiter already
+/// matched. Backslashes and braces are plain text, and the string ends at the
+/// first `"` followed by as many `#` as opened it.
+pub(crate) fn raw_string(lex: &mut logos::Lexer<Token>) -> Option<Literal> {
+    let hashes = lex.slice().len() - 2;
+    let closing = format!("\"{}", "#".repeat(hashes));
+    let rest = lex.remainder();
+    match rest.find(&closing) {
+        Some(end) => {
+            lex.bump(end + closing.len());
+            Some(Literal::String)
+        }
+        None => {
+            let span = Span::new(lex.span().start, lex.span().end + rest.len());
+            lex.extras.errors.push(LexError::new(LEXICAL_UNTERMINATED_STRING, span));
+            lex.bump(rest.len());
+            None
+        }
+    }
+}
+
+/// Reads a `"""` text block, the opening quotes already matched, and
+/// reports its invalid escapes. See [`block_lines`] for how it is decoded.
+pub(crate) fn text_block(lex: &mut logos::Lexer<Token>) -> Option<Literal> {
+    let base = lex.span().end;
+    let rest = lex.remainder();
+    let mut end = 0;
+    while !rest[end..].starts_with("\"\"\"") {
+        let mut chars = rest[end..].chars();
+        match chars.next() {
+            Some('\\') => end += 1 + chars.next().map_or(0, char::len_utf8),
+            Some(c) => end += c.len_utf8(),
+            None => {
+                let span = Span::new(lex.span().start, base + rest.len());
+                lex.extras.errors.push(LexError::new(LEXICAL_UNTERMINATED_STRING, span));
+                lex.bump(rest.len());
+                return None;
+            }
+        }
+    }
+    lex.bump(end + 3);
+
+    for (offset, line) in block_lines(&rest[..end]).into_iter().flatten() {
+        let mut i = 0;
+        while let Some(found) = line[i..].find('\\') {
+            let at = i + found;
+            let (escaped, len) = escape(&line[at + 1..]);
+            if escaped.is_none() {
+                let span = Span::new(base + offset + at, base + offset + at + 1 + len);
+                lex.extras.errors.push(LexError::new(LEXICAL_INVALID_ESCAPE, span));
+            }
+            i = at + 1 + len;
+        }
+    }
+    Some(Literal::String)
+}
+
+/// Lines of the body of a text block as they come out, each with its offset
+/// in `body`; `None` for a blank line.
+///
+/// A line break right after the opening quotes is dropped, and so is the
+/// line holding the closing quotes when nothing else is on it. The
+/// indentation common to the remaining lines, and to the closing line, is
+/// stripped; blank lines don't count towards it and come out empty. Line
+/// breaks come out as `\n`, whatever the file uses.
+fn block_lines(body: &str) -> Vec<Option<(usize, &str)>> {
+    let is_blank = |line: &str| line.trim_start_matches([' ', '\t']).is_empty();
+    let indentation = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in body.split('\n') {
+        lines.push((offset, line.strip_suffix('\r').unwrap_or(line)));
+        offset += line.len() + 1;
+    }
+    if lines.len() > 1 && is_blank(lines[0].1) {
+        lines.remove(0);
+    }
+    let closing = match lines.last() {
+        Some((_, line)) if lines.len() > 1 && is_blank(line) => lines.pop(),
+        _ => None,
+    };
+    let indent = lines
+        .iter()
+        .filter(|(_, line)| !is_blank(line))
+        .chain(closing.iter())
+        .map(|(_, line)| indentation(line))
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .into_iter()
+        .map(|(offset, line)| (!is_blank(line)).then(|| (offset + indent, &line[indent..])))
+        .collect()
+}
+
+/// `raw` with its escape sequences decoded, and `{{` and `}}` as single
+/// braces if `braces` is set. Invalid escapes are dropped; the lexer has
+/// reported them already.
+fn unescape(raw: &str, braces: bool) -> Cow<'_, str> {
+    let doubled = braces && (raw.contains("{{") || raw.contains("}}"));
+    if !doubled && !raw.contains('\\') {
+        return Cow::Borrowed(raw);
+    }
+    let mut text = String::with_capacity(raw.len());
+    let mut i = 0;
+    while let Some(c) = raw[i..].chars().next() {
+        match c {
+            '{' | '}' if braces && raw[i + 1..].starts_with(c) => {
+                text.push(c);
+                i += 2;
+            }
+            '\\' => {
+                let (escaped, len) = escape(&raw[i + 1..]);
+                text.extend(escaped);
+                i += 1 + len;
+            }
+            c => {
+                text.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    Cow::Owned(text)
+}
+
+/// Text of a string literal, interpolated string part or doc comment, given
+/// the source text of its token: decoded as the lexer would, without the
+/// delimiters. Strings are not kept decoded, so this decodes again each call.
+pub fn text(token: &str) -> Cow<'_, str> {
+    let inner = |open: usize, close: usize| token.get(open..token.len().saturating_sub(close)).unwrap_or_default();
+    if token.starts_with("///") || token.starts_with("//!") {
+        Cow::Borrowed(&token[3..])
+    } else if let Some(raw) = token.strip_prefix('r') {
+        let hashes = raw.bytes().take_while(|&b| b == b'#').count();
+        Cow::Borrowed(inner(2 + hashes, 1 + hashes))
+    } else if token.starts_with("\"\"\"") && token.len() >= 6 {
+        let mut text = String::new();
+        for (i, line) in block_lines(inner(3, 3)).into_iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            if let Some((_, line)) = line {
+                text.push_str(&unescape(line, false));
+            }
+        }
+        Cow::Owned(text)
+    } else {
+        unescape(inner(1, 1), true)
+    }
+}
+
+/// Reads the body of a `'...'` literal, the opening quote already matched.
+pub(crate) fn char(lex: &mut logos::Lexer<Token>) -> Option<Literal> {
+    let start = lex.span().start;
+    let rest = lex.remainder();
+    let mut value = Vec::new();
+    let mut valid = true;
+    let mut i = 0;
+    while let Some(c) = rest[i..].chars().next() {
+        match c {
+            '\'' => {
+                lex.bump(i + 1);
+                let span = Span::new(start, start + 2 + i);
+                return match value[..] {
+                    [value] if valid => Some(Literal::Char(value)),
+                    [] if valid => {
+                        lex.extras.errors.push(LexError::new(LEXICAL_EMPTY_CHAR, span));
+                        None
+                    }
+                    _ if valid => {
+                        lex.extras.errors.push(LexError::new(LEXICAL_MULTIPLE_CHARS, span));
+                        None
+                    }
+                    _ => None,
+                };
+            }
+            '\n' | '\r' => break,
+            '\\' => {
+                let (escaped, len) = escape(&rest[i + 1..]);
+                match escaped {
+                    Some(escaped) => value.push(escaped),
+                    None => {
+                        let span = Span::new(start + 1 + i, start + 2 + i + len);
+                        lex.extras.errors.push(LexError::new(LEXICAL_INVALID_ESCAPE, span));
+                        valid = false;
+                    }
+                }
+                i += 1 + len;
+            }
+            c => {
+                value.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+    lex.bump(i);
+    let span = Span::new(start, start + 1 + i);
+    lex.extras.errors.push(LexError::new(LEXICAL_UNTERMINATED_CHAR, span));
+    None
+}
+
+/// Reads a numeric literal: decimal, `0x`/`0o`/`0b` integers, `_` digit
+/// separators, exponents and an optional type suffix such as `u8` or `f32`.
+pub(crate) fn number(lex: &mut logos::Lexer<Token>) -> Option<Literal> {
+    let start = lex.span().start;
+    match parse_number(lex.slice()) {
+        Ok(literal) => Some(literal),
+        Err((kind, range)) => {
+            let span = Span::new(start + range.start, start + range.end);
+            lex.extras.errors.push(LexError::new(kind, span));
+            None
+        }
+    }
+}
+
+type NumberError = (LexicalErrors, Range<usize>);
+
+fn parse_number(text: &str) -> Result<Literal, NumberError> {
+    let radix = match text.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => return parse_decimal(text),
+    };
+
+    let digits = text[2..]
+        .bytes()
+        .take_while(|b| b.is_ascii_digit() || *b == b'_' || (radix == 16 && b.is_ascii_hexdigit()))
+        .count();
+    let (number, suffix) = text.split_at(2 + digits);
+    let value = parse_integer(number, 2, radix)?;
+
+    match suffix {
+        "" => Ok(unsuffixed_integer(value)),
+        _ => match Type::primitive(suffix) {
+            Some(typedef) if typedef.is_integer() => typed_integer(value, typedef, text.len()),
+            _ => Err((LEXICAL_INVALID_SUFFIX, number.len()..text.len())),
+        },
+    }
+}
+
+fn parse_decimal(text: &str) -> Result<Literal, NumberError> {
+    let digits = |from: usize| text[from..].bytes().take_while(|b| b.is_ascii_digit() || *b == b'_').count();
+    let starts_with_digit = |from: usize| text[from..].bytes().next().is_some_and(|b| b.is_ascii_digit());
+
+    let mut end = digits(0);
+    let mut float = false;
+    if text[end..].starts_with('.') && starts_with_digit(end + 1) {
+        end += 1 + digits(end + 1);
+        float = true;
+    }
+    if text[end..].starts_with(['e', 'E']) {
+        let sign = text[end + 1..].starts_with(['+', '-']) as usize;
+        if starts_with_digit(end + 1 + sign) {
+            end += 1 + sign + digits(end + 1 + sign);
+            float = true;
+        }
+    }
+    let (number, suffix) = text.split_at(end);
+
+    let typedef = match suffix {
+        "" => None,
+        _ => match Type::primitive(suffix) {
+            Some(typedef) if typedef.is_float() || (typedef.is_integer() && !float) => Some(typedef),
+            _ => return Err((LEXICAL_INVALID_SUFFIX, end..text.len())),
+        },
+    };
+
+    if float || typedef.as_ref().is_some_and(Type::is_float) {
+        let value: f64 = number.replace('_', "").parse().map_err(|_| (LEXICAL_INVALID_NUMBER, 0..end))?;
+        return match typedef {
+            Some(Type::F32) if value.abs() > f32::MAX as f64 => Err((LEXICAL_NUMBER_OVERFLOW, 0..text.len())),
+            Some(typedef) => Ok(Literal::TypedFloat(value, typedef)),
+            None if value.is_infinite() => Err((LEXICAL_NUMBER_OVERFLOW, 0..text.len())),
+            None if value.abs() > f32::MAX as f64 => Ok(Literal::Double(value)),
+            None => Ok(Literal::Float(value)),
+        };
+    }
+
+    let value = parse_integer(number, 0, 10)?;
+    match typedef {
+        Some(typedef) => typed_integer(value, typedef, text.len()),
+        None => Ok(unsuffixed_integer(value)),
+    }
+}
+
+/// Accumulates the digits of `number` after its `skip`-byte radix prefix.
+fn parse_integer(number: &str, skip: usize, radix: u32) -> Result<u64, NumberError> {
+    let mut value: u64 = 0;
+    let mut empty = true;
+    for (i, c) in number.char_indices().skip(skip) {
+        if c == '_' {
+            continue;
+        }
+        let digit = c.to_digit(radix).ok_or((LEXICAL_INVALID_DIGIT, i..i + 1))?;
+        value = value
+            .checked_mul(radix as u64)
+            .and_then(|value| value.checked_add(digit as u64))
+            .ok_or((LEXICAL_NUMBER_OVERFLOW, 0..number.len()))?;
+        empty = false;
+    }
+    if empty {
+        return Err((LEXICAL_MISSING_DIGITS, 0..number.len()));
+    }
+    Ok(value)
+}
+
+fn unsuffixed_integer(value: u64) -> Literal {
+    if let Ok(value) = i32::try_from(value) {
+        return Literal::Integer(value);
+    }
+    match i64::try_from(value) {
+        Ok(value) => Literal::Long(value),
+        Err(_) => Literal::ULong(value),
+    }
+}
+
+/// Literals carry no sign, so a signed type accepts up to the magnitude of its
+/// minimum here; the parser checks the exact range once a unary `-` is seen.
+fn typed_integer(value: u64, typedef: Type, len: usize) -> Result<Literal, NumberError> {
+    let limit = typedef.integer_limit(false).max(typedef.integer_limit(true));
+    match limit {
+        Some(limit) if value <= limit => Ok(Literal::TypedInteger(value, typedef)),
+        _ => Err((LEXICAL_NUMBER_OVERFLOW, 0..len)),
+    }
+}
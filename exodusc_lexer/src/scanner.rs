@@ -1,42 +1,89 @@
 #![allow(non_camel_case_types)]
 
 use super::types::Type;
+use crate::{
+    errors::{LexError, LexicalErrors},
+    literals,
+    span::Span,
+    symbol::Symbol,
+};
 use logos::Logos;
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum Keywords {
-    Let,
-    If,
-    Else,
-    Func,
-    While,
-    Return,
+/// State shared with the token callbacks while lexing.
+#[derive(Default)]
+pub struct Extras {
+    pub errors: Vec<LexError>,
+    /// Set when a string literal stopped at an interpolation `{`.
+    pub interpolation: bool,
+}
+
+/// Declares [`Keywords`] from one list of variants and their spellings, so
+/// the enum, [`Keywords::ALL`] and [`Keywords::as_str`] cannot drift apart.
+macro_rules! keywords {
+    ($($keyword:ident => $text:literal,)*) => {
+        #[derive(Debug, PartialEq, Clone, Copy)]
+        pub enum Keywords {
+            $($keyword,)*
+        }
+
+        impl Keywords {
+            /// Every keyword. Each one lexes as a [`Token::Keyword`], so none
+            /// of them can name a variable, parameter, function, field or type.
+            pub const ALL: [Keywords; [$($text),*].len()] = [$(Keywords::$keyword),*];
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Keywords::$keyword => $text,)*
+                }
+            }
+        }
+    };
+}
+
+keywords! {
+    Let => "let",
+    If => "if",
+    Else => "else",
+    Func => "func",
+    While => "while",
+    Return => "return",
+    For => "for",
+    Break => "break",
+    Continue => "continue",
+    Struct => "struct",
+    Enum => "enum",
+    Import => "import",
+    Const => "const",
+    Match => "match",
+    Pub => "pub",
+    As => "as",
+    In => "in",
+    Extern => "extern",
+    Mut => "mut",
+    Loop => "loop",
 }
 
 impl std::fmt::Display for Keywords {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Keywords::Let => write!(f, "let"),
-            Keywords::If => write!(f, "if"),
-            Keywords::Else => write!(f, "else"),
-            Keywords::Func => write!(f, "func"),
-            Keywords::Return => write!(f, "return"),
-            Keywords::While => write!(f, "while"),
-        }
+        write!(f, "{}", self.as_str())
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Literal {
     Default,
     Char(char),
     Integer(i32),
     Long(i64),
+    ULong(u64),
     Float(f64),
     Double(f64),
-    String(String),
+    TypedInteger(u64, Type),
+    TypedFloat(f64, Type),
+    /// Its text is read from the source, see [`crate::Lexer::text`].
+    String,
     Boolean(bool),
-    Var(String),
+    Var(Symbol),
 }
 
 impl std::fmt::Display for Literal {
@@ -45,9 +92,12 @@ impl std::fmt::Display for Literal {
             Literal::Char(c) => write!(f, "{}", c),
             Literal::Integer(i) => write!(f, "{}", i),
             Literal::Long(l) => write!(f, "{}", l),
+            Literal::ULong(l) => write!(f, "{}", l),
             Literal::Float(flt) => write!(f, "{}", flt),
             Literal::Double(d) => write!(f, "{}", d),
-            Literal::String(s) => write!(f, "{}", s),
+            Literal::TypedInteger(i, typedef) => write!(f, "{}{}", i, typedef),
+            Literal::TypedFloat(flt, typedef) => write!(f, "{}{}", flt, typedef),
+            Literal::String => write!(f, "\"…\""),
             Literal::Boolean(b) => write!(f, "{}", b),
             Literal::Var(v) => write!(f, "{}", v),
             Literal::Default => write!(f, "??"),
@@ -55,29 +105,86 @@ impl std::fmt::Display for Literal {
     }
 }
 
-#[derive(Logos, PartialEq, Clone, Debug)]
-pub enum Token {
-    #[regex("[a-zA-Z]+", |lexer| lexer.slice().to_owned())]
-    Identifier(String),
+/// A `///` or `//!` comment. Its text is read from the source, see
+/// [`crate::Lexer::text`].
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum DocComment {
+    Outer,
+    Inner,
+}
 
-    #[regex("-?[0-9]+", |lexer| {
-        let number : i64 = lexer.slice().parse().expect("Can't parse number!");
-        if number > i32::MAX as i64 {
-            return Literal::Long(number);
+impl std::fmt::Display for DocComment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocComment::Outer => write!(f, "///…"),
+            DocComment::Inner => write!(f, "//!…"),
         }
-        return Literal::Integer(number as i32);
-    })]
-    #[regex("[0-9]*\\.[0-9]+([eE][+-]?[0-9]+)?|[0-9]+[eE][+-]?[0-9]+", |lexer| {
-        let number : f64 = lexer.slice().parse().expect("Can't parse number!");
-        if number > f32::MAX as f64 {
-            return Literal::Double(number);
+    }
+}
+
+/// Text pieces of an interpolated string such as `"a {x} b {y} c"`, which
+/// lexes as `Head` (`"a {`), `x`, `Middle` (`} b {`), `y`, `Tail` (`} c"`).
+/// Each part's span includes the quote and brace delimiters around its text,
+/// which is read from the source, see [`crate::Lexer::text`].
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum StringPart {
+    Head,
+    Middle,
+    Tail,
+}
+
+impl std::fmt::Display for StringPart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringPart::Head => write!(f, "\"…{{"),
+            StringPart::Middle => write!(f, "}}…{{"),
+            StringPart::Tail => write!(f, "}}…\""),
         }
-        Literal::Float(number)
-    })]
-    #[regex(r#""[^"]*""#, |lexer| {
-        let string: String = lexer.slice()[1..(lexer.slice().len()-1)].to_owned();
-        Literal::String(string)
-    })]
+    }
+}
+
+/// Consumes the rest of a `/* */` comment, honoring nested `/* */` pairs.
+/// Returns `false` when the comment is never closed.
+fn block_comment(lex: &mut logos::Lexer<Token>) -> bool {
+    let rest = lex.remainder().as_bytes();
+    let mut depth = 1;
+    let mut i = 0;
+    while i < rest.len() {
+        match (rest[i], rest.get(i + 1)) {
+            (b'/', Some(b'*')) => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', Some(b'/')) => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    lex.bump(i);
+                    return true;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    let span = Span::new(lex.span().start, lex.span().end + rest.len());
+    lex.extras.errors.push(LexError::new(LexicalErrors::LEXICAL_UNTERMINATED_COMMENT, span));
+    lex.bump(rest.len());
+    false
+}
+
+#[derive(Logos, PartialEq, Clone, Copy, Debug)]
+#[logos(extras = Extras)]
+pub enum Token {
+    #[regex(r"[_\p{XID_Start}]\p{XID_Continue}*", |lexer| Symbol::intern(lexer.slice()))]
+    Identifier(Symbol),
+
+    #[regex("[0-9][0-9a-zA-Z_]*", literals::number)]
+    #[regex("[0-9][0-9_]*\\.[0-9][0-9a-zA-Z_]*", literals::number)]
+    #[regex("[0-9][0-9_]*(\\.[0-9][0-9_]*)?[eE][+-][0-9][0-9a-zA-Z_]*", literals::number)]
+    #[regex("\\.[0-9][0-9_]*([eE][+-]?[0-9][0-9_]*)?[a-zA-Z0-9_]*", literals::number)]
+    #[token("\"", literals::string)]
+    #[regex("r#*\"", literals::raw_string)]
+    #[token("\"\"\"", literals::text_block)]
     #[regex("(true|false)", |lex| {
         match lex.slice(){
             "true" => Ok(Literal::Boolean(true)),
@@ -85,43 +192,20 @@ pub enum Token {
             _=> Err(())
         }
     })]
-    #[regex(r#"'[^']*'"#, |lexer|{
-        let text = &lexer.slice()[1..(lexer.slice().len()-1)];
-        Literal::Char(text.chars().next().expect("Invalid character literal"))
-    })]
+    #[token("'", literals::char)]
     Literal(Literal),
 
-    #[regex("let|if|else|func|return|while", |lex|{
-        match lex.slice() {
-            "let" => Keywords::Let,
-            "if" => Keywords::If,
-            "else" => Keywords::Else,
-            "func" => Keywords::Func,
-            "return" => Keywords::Return,
-            "while" => Keywords::While,
-            _ => panic!("Unrecognized Keyword"),
-        }
+    /// Produced by [`crate::Lexer`], which resumes the string after each
+    /// embedded expression.
+    Interpolation(StringPart),
+
+    // logos needs the words spelled out; tests check them against `Keywords::ALL`.
+    #[regex("let|if|else|func|return|while|for|break|continue|struct|enum|import|const|match|pub|as|in|extern|mut|loop", |lex| {
+        Keywords::ALL.into_iter().find(|keyword| keyword.as_str() == lex.slice())
     })]
     Keyword(Keywords),
 
-    #[regex("(i8|i16|i32|i64|u8|u16|u32|u64|f32|f64|string|char|bool)", |lex|{
-        match lex.slice() {
-            "i8" => Type::I8,
-            "i16" => Type::I16,
-            "i32" => Type::I32,
-            "i64" => Type::I64,
-            "u8" => Type::U8,
-            "u16" => Type::U16,
-            "u32" => Type::U32,
-            "u64" => Type::U64,
-            "f32" => Type::F32,
-            "f64" => Type::F64,
-            "char" => Type::Char,
-            "string" => Type::String,
-            "bool" => Type::Boolean,
-            _ => panic!("Unrecognized Type"), // substituindo todo!() por panic!()
-        }
-    })]
+    #[regex("(i8|i16|i32|i64|u8|u16|u32|u64|f32|f64|string|char|bool)", |lex| Type::primitive(lex.slice()))]
     Type(Type),
 
     #[token(".")]
@@ -142,7 +226,11 @@ pub enum Token {
     LBrace,
     #[token("}")]
     RBrace,
-    #[regex("\\^|\\+|\\-|/|%|\\&|<|>|\\&&|==|!=|\\*|\\|\\||!||->|=|::", |lex|{
+    #[token("[")]
+    LBracket,
+    #[token("]")]
+    RBracket,
+    #[regex(r"\+|-|\*|/|%|==|!=|<|>|<=|>=|&&|\|\||&|\||\^|<<|>>|!|~|->|::|\.\.|\.\.=|\?|=|=>|\+=|-=|\*=|/=|%=|&=|\|=|\^=|<<=|>>=", |lex|{
         match lex.slice() {
             "+" => Operator::ADD,
             "-" => Operator::SUB,
@@ -150,23 +238,52 @@ pub enum Token {
             "/" => Operator::DIV,
             "%" => Operator::MOD,
             "==" => Operator::EQUAL,
+            "!=" => Operator::NOTEQ,
             "<" => Operator::LT,
             ">" => Operator::GT,
-            "!=" => Operator::NOTEQ,
+            "<=" => Operator::LTEQ,
+            ">=" => Operator::GTEQ,
             "&&" => Operator::AND,
             "||" => Operator::OR,
             "&" => Operator::ADDRESSING,
+            "|" => Operator::BITOR,
+            "^" => Operator::XOR,
+            "<<" => Operator::SHL,
+            ">>" => Operator::SHR,
             "!" => Operator::NOT,
+            "~" => Operator::BITNOT,
             "->" => Operator::ARROW,
             "::" => Operator::NAVIGATION,
+            ".." => Operator::RANGE,
+            "..=" => Operator::RANGE_INCLUSIVE,
+            "?" => Operator::QUESTION,
             "=" => Operator::ASSIGNMENT,
-            _ => panic!("Unrecognized Operator"),
+            "=>" => Operator::FAT_ARROW,
+            "+=" => Operator::ADD_ASSIGNMENT,
+            "-=" => Operator::SUB_ASSIGNMENT,
+            "*=" => Operator::MUL_ASSIGNMENT,
+            "/=" => Operator::DIV_ASSIGNMENT,
+            "%=" => Operator::MOD_ASSIGNMENT,
+            "&=" => Operator::BITAND_ASSIGNMENT,
+            "|=" => Operator::BITOR_ASSIGNMENT,
+            "^=" => Operator::XOR_ASSIGNMENT,
+            "<<=" => Operator::SHL_ASSIGNMENT,
+            ">>=" => Operator::SHR_ASSIGNMENT,
+            _ => unreachable!("operator regex and match arms out of sync"),
         }
     })]
     Operator(Operator),
-    #[token("\n")]
+    #[regex("///([^/\r\n][^\r\n]*)?", |_| DocComment::Outer, priority = 10)]
+    #[regex("//![^\r\n]*", |_| DocComment::Inner, priority = 10)]
+    DocComment(DocComment),
+    #[regex("//[^\r\n]*")]
+    #[token("/*", block_comment)]
+    Comment,
+    /// `\n`, `\r\n` or a lone `\r`.
+    #[regex("\r\n|\r|\n")]
     Line,
-    #[regex(r"[ \t\f]")]
+    /// A run of spaces, tabs and form feeds.
+    #[regex(r"[ \t\f]+")]
     Space,
     #[error]
     Error,
@@ -175,11 +292,137 @@ pub enum Token {
 }
 
 
+impl Token {
+    /// Whitespace, newlines and plain comments, which carry no meaning for the parser.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, Token::Space | Token::Line | Token::Comment)
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Token::Identifier(_) => TokenKind::Identifier,
+            Token::Literal(_) => TokenKind::Literal,
+            Token::Interpolation(_) => TokenKind::Interpolation,
+            Token::Keyword(_) => TokenKind::Keyword,
+            Token::Type(_) => TokenKind::Type,
+            Token::Dot => TokenKind::Dot,
+            Token::Comma => TokenKind::Comma,
+            Token::Colon => TokenKind::Colon,
+            Token::Semicolon => TokenKind::Semicolon,
+            Token::AtSign => TokenKind::AtSign,
+            Token::LParen => TokenKind::LParen,
+            Token::RParen => TokenKind::RParen,
+            Token::LBrace => TokenKind::LBrace,
+            Token::RBrace => TokenKind::RBrace,
+            Token::LBracket => TokenKind::LBracket,
+            Token::RBracket => TokenKind::RBracket,
+            Token::Operator(_) => TokenKind::Operator,
+            Token::DocComment(_) => TokenKind::DocComment,
+            Token::Comment => TokenKind::Comment,
+            Token::Line => TokenKind::Line,
+            Token::Space => TokenKind::Space,
+            Token::Error => TokenKind::Error,
+            Token::EOF => TokenKind::EOF,
+        }
+    }
+}
+
+/// A [`Token`] without its payload, for cheap comparisons and [`TokenSet`]s.
+///
+/// [`TokenSet`]: crate::token_set::TokenSet
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum TokenKind {
+    Identifier,
+    Literal,
+    Interpolation,
+    Keyword,
+    Type,
+    Dot,
+    Comma,
+    Colon,
+    Semicolon,
+    AtSign,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Operator,
+    DocComment,
+    Comment,
+    Line,
+    Space,
+    Error,
+    EOF,
+}
+
+impl TokenKind {
+    pub const ALL: [TokenKind; 23] = [
+        TokenKind::Identifier,
+        TokenKind::Literal,
+        TokenKind::Interpolation,
+        TokenKind::Keyword,
+        TokenKind::Type,
+        TokenKind::Dot,
+        TokenKind::Comma,
+        TokenKind::Colon,
+        TokenKind::Semicolon,
+        TokenKind::AtSign,
+        TokenKind::LParen,
+        TokenKind::RParen,
+        TokenKind::LBrace,
+        TokenKind::RBrace,
+        TokenKind::LBracket,
+        TokenKind::RBracket,
+        TokenKind::Operator,
+        TokenKind::DocComment,
+        TokenKind::Comment,
+        TokenKind::Line,
+        TokenKind::Space,
+        TokenKind::Error,
+        TokenKind::EOF,
+    ];
+}
+
+/// How the kind reads in a diagnostic: punctuation is quoted, everything
+/// else is named.
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Identifier => write!(f, "identifier"),
+            TokenKind::Literal => write!(f, "literal"),
+            TokenKind::Interpolation => write!(f, "interpolated string"),
+            TokenKind::Keyword => write!(f, "keyword"),
+            TokenKind::Type => write!(f, "type"),
+            TokenKind::Dot => write!(f, "`.`"),
+            TokenKind::Comma => write!(f, "`,`"),
+            TokenKind::Colon => write!(f, "`:`"),
+            TokenKind::Semicolon => write!(f, "`;`"),
+            TokenKind::AtSign => write!(f, "`@`"),
+            TokenKind::LParen => write!(f, "`(`"),
+            TokenKind::RParen => write!(f, "`)`"),
+            TokenKind::LBrace => write!(f, "`{{`"),
+            TokenKind::RBrace => write!(f, "`}}`"),
+            TokenKind::LBracket => write!(f, "`[`"),
+            TokenKind::RBracket => write!(f, "`]`"),
+            TokenKind::Operator => write!(f, "operator"),
+            TokenKind::DocComment => write!(f, "doc comment"),
+            TokenKind::Comment => write!(f, "comment"),
+            TokenKind::Line => write!(f, "line break"),
+            TokenKind::Space => write!(f, "whitespace"),
+            TokenKind::Error => write!(f, "invalid token"),
+            TokenKind::EOF => write!(f, "end of file"),
+        }
+    }
+}
+
 impl std::fmt::Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Token::Identifier(idenf) => write!(f, "{idenf}"),
             Token::Literal(liteal) => write!(f, "{liteal}"),
+            Token::Interpolation(part) => write!(f, "{part}"),
             Token::Keyword(keyword) => write!(f, "{keyword}"),
             Token::Type(typedef) => write!(f, "{typedef}"),
             Token::Dot => write!(f, "."),
@@ -191,7 +434,11 @@ impl std::fmt::Display for Token {
             Token::RParen => write!(f, ")"),
             Token::LBrace => write!(f, "{{"),
             Token::RBrace => write!(f, "}}"),
+            Token::LBracket => write!(f, "["),
+            Token::RBracket => write!(f, "]"),
             Token::Operator(op) => write!(f, "{op}"),
+            Token::DocComment(doc) => write!(f, "{doc}"),
+            Token::Comment => write!(f, "comment"),
             Token::Line => write!(f, "\\n"),
             Token::Space => write!(f, " "),
             Token::Error => write!(f, ""),
@@ -200,7 +447,13 @@ impl std::fmt::Display for Token {
     }
 }
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Operator {
     ADD,
     SUB,
@@ -210,14 +463,74 @@ pub enum Operator {
     EQUAL,
     LT,
     GT,
+    LTEQ,
+    GTEQ,
     AND,
     NOTEQ,
     OR,
     ADDRESSING,
+    BITOR,
+    XOR,
+    SHL,
+    SHR,
     NOT,
+    BITNOT,
     ARROW,
     NAVIGATION,
+    RANGE,
+    RANGE_INCLUSIVE,
+    QUESTION,
+    FAT_ARROW,
     ASSIGNMENT,
+    ADD_ASSIGNMENT,
+    SUB_ASSIGNMENT,
+    MUL_ASSIGNMENT,
+    DIV_ASSIGNMENT,
+    MOD_ASSIGNMENT,
+    BITAND_ASSIGNMENT,
+    BITOR_ASSIGNMENT,
+    XOR_ASSIGNMENT,
+    SHL_ASSIGNMENT,
+    SHR_ASSIGNMENT,
+}
+
+impl Operator {
+    /// Binding power and associativity of the operator in infix position,
+    /// where a higher value binds tighter. `None` for prefix/postfix-only
+    /// operators and for `->` and `=>`.
+    pub fn precedence(&self) -> Option<(u8, Associativity)> {
+        use Associativity::*;
+        let precedence = match self {
+            Self::ASSIGNMENT
+            | Self::ADD_ASSIGNMENT
+            | Self::SUB_ASSIGNMENT
+            | Self::MUL_ASSIGNMENT
+            | Self::DIV_ASSIGNMENT
+            | Self::MOD_ASSIGNMENT
+            | Self::BITAND_ASSIGNMENT
+            | Self::BITOR_ASSIGNMENT
+            | Self::XOR_ASSIGNMENT
+            | Self::SHL_ASSIGNMENT
+            | Self::SHR_ASSIGNMENT => (1, Right),
+            Self::RANGE | Self::RANGE_INCLUSIVE => (2, Left),
+            Self::OR => (3, Left),
+            Self::AND => (4, Left),
+            Self::EQUAL | Self::NOTEQ | Self::LT | Self::GT | Self::LTEQ | Self::GTEQ => (5, Left),
+            Self::BITOR => (6, Left),
+            Self::XOR => (7, Left),
+            Self::ADDRESSING => (8, Left),
+            Self::SHL | Self::SHR => (9, Left),
+            Self::ADD | Self::SUB => (10, Left),
+            Self::MUL | Self::DIV | Self::MOD => (11, Left),
+            Self::NAVIGATION => (12, Left),
+            Self::NOT | Self::BITNOT | Self::QUESTION | Self::ARROW | Self::FAT_ARROW => return None,
+        };
+        Some(precedence)
+    }
+
+    pub fn is_assignment(&self) -> bool {
+        self.precedence() == Some((1, Associativity::Right))
+    }
 }
 
 impl std::fmt::Display for Operator {
@@ -227,18 +540,39 @@ impl std::fmt::Display for Operator {
             Self::SUB => write!(f, "-"),
             Self::MUL => write!(f, "*"),
             Self::DIV => write!(f, "/"),
-            Self::MOD => write!(f, "&"),
+            Self::MOD => write!(f, "%"),
             Self::EQUAL => write!(f, "=="),
             Self::LT => write!(f, "<"),
             Self::GT => write!(f, ">"),
+            Self::LTEQ => write!(f, "<="),
+            Self::GTEQ => write!(f, ">="),
             Self::AND => write!(f, "&&"),
             Self::NOTEQ => write!(f, "!="),
             Self::OR => write!(f, "||"),
             Self::ADDRESSING => write!(f, "&"),
+            Self::BITOR => write!(f, "|"),
+            Self::XOR => write!(f, "^"),
+            Self::SHL => write!(f, "<<"),
+            Self::SHR => write!(f, ">>"),
             Self::NOT => write!(f, "!"),
+            Self::BITNOT => write!(f, "~"),
             Self::ARROW => write!(f, "->"),
             Self::ASSIGNMENT => write!(f, "="),
             Self::NAVIGATION => write!(f, "::"),
+            Self::RANGE => write!(f, ".."),
+            Self::RANGE_INCLUSIVE => write!(f, "..="),
+            Self::QUESTION => write!(f, "?"),
+            Self::FAT_ARROW => write!(f, "=>"),
+            Self::ADD_ASSIGNMENT => write!(f, "+="),
+            Self::SUB_ASSIGNMENT => write!(f, "-="),
+            Self::MUL_ASSIGNMENT => write!(f, "*="),
+            Self::DIV_ASSIGNMENT => write!(f, "/="),
+            Self::MOD_ASSIGNMENT => write!(f, "%="),
+            Self::BITAND_ASSIGNMENT => write!(f, "&="),
+            Self::BITOR_ASSIGNMENT => write!(f, "|="),
+            Self::XOR_ASSIGNMENT => write!(f, "^="),
+            Self::SHL_ASSIGNMENT => write!(f, "<<="),
+            Self::SHR_ASSIGNMENT => write!(f, ">>="),
         }
     }
 }
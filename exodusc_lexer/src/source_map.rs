@@ -0,0 +1,185 @@
+/// Unit in which a column is counted.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColumnUnit {
+    /// UTF-8 bytes since the start of the line.
+    Utf8,
+    /// Unicode scalar values since the start of the line.
+    Char,
+    /// UTF-16 code units, as used by the Language Server Protocol.
+    Utf16,
+    /// Terminal cells, with tabs expanded to the next multiple of the width.
+    Display { tab_width: usize },
+}
+
+impl ColumnUnit {
+    /// Width of `text` measured in this unit.
+    pub fn measure(self, text: &str) -> usize {
+        text.chars().fold(0, |column, c| self.advance(column, c))
+    }
+
+    fn advance(self, column: usize, c: char) -> usize {
+        match self {
+            ColumnUnit::Utf8 => column + c.len_utf8(),
+            ColumnUnit::Char => column + 1,
+            ColumnUnit::Utf16 => column + c.len_utf16(),
+            ColumnUnit::Display { tab_width } if c == '\t' && tab_width > 0 => {
+                column + tab_width - column % tab_width
+            }
+            ColumnUnit::Display { .. } => column + 1,
+        }
+    }
+}
+
+/// Line (1-based) and column (0-based) of a byte offset.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Byte offsets at which every line of a source starts and ends.
+///
+/// Built once in a single pass, so offset ↔ line lookups are a binary search
+/// instead of a rescan of the source. Lines end at `\n`, `\r\n` or a lone
+/// `\r`, and a leading byte order mark is not part of the first line.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+    line_ends: Vec<usize>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let bytes = source.as_bytes();
+        let mut line_starts = vec![if source.starts_with('\u{FEFF}') { 3 } else { 0 }];
+        let mut line_ends = Vec::new();
+        for (i, &byte) in bytes.iter().enumerate() {
+            match byte {
+                b'\n' if i > 0 && bytes[i - 1] == b'\r' => line_ends.push(i - 1),
+                b'\n' => line_ends.push(i),
+                b'\r' if bytes.get(i + 1) != Some(&b'\n') => line_ends.push(i),
+                _ => continue,
+            }
+            line_starts.push(i + 1);
+        }
+        line_ends.push(source.len());
+        Self { line_starts, line_ends, len: source.len() }
+    }
+
+    /// Number of lines; an empty source still has one (empty) line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Line (1-based) containing a byte offset. Offsets past the end belong
+    /// to the last line.
+    pub fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset).max(1)
+    }
+
+    /// Byte range of a line (1-based), without its line terminator.
+    pub fn line_span(&self, line: usize) -> Option<std::ops::Range<usize>> {
+        let index = line.checked_sub(1)?;
+        Some(*self.line_starts.get(index)?..self.line_ends[index])
+    }
+
+    /// Line and column of a byte offset in `source`, which must be the text
+    /// this index was built from.
+    pub fn location(&self, source: &str, offset: usize, unit: ColumnUnit) -> Location {
+        let offset = offset.min(self.len);
+        let line = self.line(offset);
+        let start = self.line_starts[line - 1];
+        Location { line, column: unit.measure(&source[start..offset.max(start)]) }
+    }
+
+    /// Byte offset of a line (1-based) and column in `unit`, the inverse of
+    /// [`LineIndex::location`]. Columns past the end of the line clamp to it;
+    /// a column inside a multi-unit character resolves to that character.
+    pub fn offset(&self, source: &str, line: usize, column: usize, unit: ColumnUnit) -> Option<usize> {
+        let span = self.line_span(line)?;
+        let mut width = 0;
+        for (i, c) in source[span.clone()].char_indices() {
+            width = unit.advance(width, c);
+            if width > column {
+                return Some(span.start + i);
+            }
+        }
+        Some(span.end)
+    }
+}
+
+/// Handle to a file registered in a [`SourceMap`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct FileId(u32);
+
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    id: FileId,
+    name: String,
+    source: String,
+    lines: LineIndex,
+}
+
+impl SourceFile {
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn lines(&self) -> &LineIndex {
+        &self.lines
+    }
+
+    pub fn location(&self, offset: usize, unit: ColumnUnit) -> Location {
+        self.lines.location(&self.source, offset, unit)
+    }
+
+    pub fn offset(&self, line: usize, column: usize, unit: ColumnUnit) -> Option<usize> {
+        self.lines.offset(&self.source, line, column, unit)
+    }
+
+    /// Text of a line (1-based), without its line terminator.
+    pub fn line_text(&self, line: usize) -> Option<&str> {
+        self.lines.line_span(line).map(|span| &self.source[span])
+    }
+}
+
+/// Owns every source file of a compilation and resolves locations in them.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: impl Into<String>, source: impl Into<String>) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        let source = source.into();
+        let lines = LineIndex::new(&source);
+        self.files.push(SourceFile { id, name: name.into(), source, lines });
+        id
+    }
+
+    pub fn get(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0 as usize]
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &SourceFile> {
+        self.files.iter()
+    }
+
+    pub fn location(&self, id: FileId, offset: usize, unit: ColumnUnit) -> Location {
+        self.get(id).location(offset, unit)
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+    sync::{Mutex, OnceLock},
+};
+
+/// An interned identifier: a `u32` index into the global symbol table.
+///
+/// Interned text lives for the rest of the process, so symbols are `Copy`,
+/// compare in O(1) and resolve back to a `&'static str` without a lexer.
+/// Only identifiers are interned, whose number is bounded by the names a
+/// program uses; string literals and doc comments are read from the source
+/// instead, see [`Lexer::text`](crate::Lexer::text).
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Symbol(u32);
+
+/// Multiply-rotate hash, as in rustc's `FxHasher`. Identifiers are short and
+/// not attacker-chosen keys of a long-lived server, so the DoS resistance of
+/// the default SipHash buys nothing here.
+#[derive(Default)]
+struct SymbolHasher(u64);
+
+impl Hasher for SymbolHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, byte: u8) {
+        self.write_u64(byte as u64);
+    }
+
+    fn write_u64(&mut self, word: u64) {
+        self.0 = (self.0.rotate_left(5) ^ word).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+type SymbolMap = HashMap<&'static str, Symbol, BuildHasherDefault<SymbolHasher>>;
+
+#[derive(Default)]
+struct Interner {
+    symbols: SymbolMap,
+    strings: Vec<&'static str>,
+}
+
+fn interner() -> &'static Mutex<Interner> {
+    static INTERNER: OnceLock<Mutex<Interner>> = OnceLock::new();
+    INTERNER.get_or_init(Default::default)
+}
+
+thread_local! {
+    /// Symbols this thread has interned before, found without taking the
+    /// global lock.
+    static CACHE: RefCell<SymbolMap> = RefCell::default();
+    /// This thread's copy of the start of the global table, extended from it
+    /// when a newer symbol is resolved.
+    static STRINGS: RefCell<Vec<&'static str>> = RefCell::default();
+}
+
+impl Symbol {
+    /// Symbol of `text`, adding it to the table if it is new. New text is
+    /// leaked, never freed.
+    pub fn intern(text: &str) -> Symbol {
+        if let Some(symbol) = CACHE.with(|cache| cache.borrow().get(text).copied()) {
+            return symbol;
+        }
+        let (text, symbol) = Self::intern_global(text);
+        CACHE.with(|cache| cache.borrow_mut().insert(text, symbol));
+        symbol
+    }
+
+    fn intern_global(text: &str) -> (&'static str, Symbol) {
+        let mut interner = interner().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((text, symbol)) = interner.symbols.get_key_value(text) {
+            return (text, *symbol);
+        }
+        let text: &'static str = Box::leak(text.into());
+        let symbol = Symbol(interner.strings.len() as u32);
+        interner.strings.push(text);
+        interner.symbols.insert(text, symbol);
+        (text, symbol)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        let index = self.0 as usize;
+        if let Some(text) = STRINGS.with(|strings| strings.borrow().get(index).copied()) {
+            return text;
+        }
+        let interner = interner().lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        STRINGS.with(|strings| {
+            let mut strings = strings.borrow_mut();
+            let known = strings.len();
+            strings.extend_from_slice(&interner.strings[known..]);
+            strings[index]
+        })
+    }
+
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(text: &str) -> Self {
+        Symbol::intern(text)
+    }
+}
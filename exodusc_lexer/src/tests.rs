@@ -1,15 +1,30 @@
 use crate::{
-    scanner::{Keywords, Operator, Token},
-    Lexer,
+    errors::{LexError, LexWarning, LexicalErrors, LexicalWarnings},
+    incremental::{ChangedTokens, TextEdit},
+    scanner::{DocComment, Keywords, Literal, Operator, StringPart, Token, TokenKind},
+    source_map::{ColumnUnit, LineIndex, Location, SourceMap},
+    span::Span,
+    symbol::Symbol,
+    token_set::TokenSet,
+    trivia::TriviaKind,
+    types::Type,
+    unicode,
+    Lexer, TokenSet, TokenStream,
 };
 
+/// The next token and its text as [`Lexer::text`] decodes it.
+fn next_text(lexer: &mut Lexer) -> (Token, String) {
+    let token = lexer.next_token();
+    (token, lexer.text(lexer.span()).into_owned())
+}
+
 #[test]
 fn tokenization_identifier() {
     let source = "x";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Identifier(String::from("x")));
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::EOF);
 }
 
 #[test]
@@ -17,8 +32,8 @@ fn tokenization_number_integer() {
     let source = "256";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Literal(crate::scanner::Literal::Integer(256)));
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Literal(crate::scanner::Literal::Integer(256)));
+    assert_eq!(lexer.next_token(), Token::EOF);
 }
 
 #[test]
@@ -26,8 +41,8 @@ fn tokenization_number_floating() {
     let source = "73.81";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Literal(crate::scanner::Literal::Float(73.81)));
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Literal(crate::scanner::Literal::Float(73.81)));
+    assert_eq!(lexer.next_token(), Token::EOF);
 }
 
 #[test]
@@ -35,8 +50,8 @@ fn tokenization_type_i32() {
     let source = "i32";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Type(crate::types::Type::I32));
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Type(crate::types::Type::I32));
+    assert_eq!(lexer.next_token(), Token::EOF);
 
 }
 
@@ -45,8 +60,8 @@ fn tokenization_type_f32() {
     let source = "f32";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Type(crate::types::Type::F32));
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Type(crate::types::Type::F32));
+    assert_eq!(lexer.next_token(), Token::EOF);
 }
 
 #[test]
@@ -54,12 +69,12 @@ fn tokenization_let_keyword() {
     let source = "let x = 2;";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Keyword(Keywords::Let));
-    assert_eq!(lexer.next(), Token::Identifier(String::from("x")));
-    assert_eq!(lexer.next(), Token::Operator(Operator::ASSIGNMENT));
-    assert_eq!(lexer.next(), Token::Literal(crate::scanner::Literal::Integer(2)));
-    assert_eq!(lexer.next(), Token::Semicolon);
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::ASSIGNMENT));
+    assert_eq!(lexer.next_token(), Token::Literal(crate::scanner::Literal::Integer(2)));
+    assert_eq!(lexer.next_token(), Token::Semicolon);
+    assert_eq!(lexer.next_token(), Token::EOF);
 
 }
 
@@ -68,14 +83,1053 @@ fn tokenization_if_keyword() {
     let source = "if (2 > 5) { }";
 
     let mut lexer = Lexer::tokenization("main", source);
-    assert_eq!(lexer.next(), Token::Keyword(Keywords::If));
-    assert_eq!(lexer.next(), Token::LParen);
-    assert_eq!(lexer.next(), Token::Literal(crate::scanner::Literal::Integer(2)));
-    assert_eq!(lexer.next(), Token::Operator(Operator::GT));
-    assert_eq!(lexer.next(), Token::Literal(crate::scanner::Literal::Integer(5)));
-    assert_eq!(lexer.next(), Token::RParen);
-    assert_eq!(lexer.next(), Token::LBrace);
-    assert_eq!(lexer.next(), Token::RBrace);
-    assert_eq!(lexer.next(), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::If));
+    assert_eq!(lexer.next_token(), Token::LParen);
+    assert_eq!(lexer.next_token(), Token::Literal(crate::scanner::Literal::Integer(2)));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::GT));
+    assert_eq!(lexer.next_token(), Token::Literal(crate::scanner::Literal::Integer(5)));
+    assert_eq!(lexer.next_token(), Token::RParen);
+    assert_eq!(lexer.next_token(), Token::LBrace);
+    assert_eq!(lexer.next_token(), Token::RBrace);
+    assert_eq!(lexer.next_token(), Token::EOF);
+
+}
+
+#[test]
+fn tokenization_spans() {
+    let source = "let name = \"héllo\";";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+    assert_eq!(lexer.span(), Span::new(0, 3));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("name")));
+    assert_eq!(lexer.span(), Span::new(4, 8));
+    assert_eq!(lexer.last_span(), Span::new(0, 3));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::ASSIGNMENT));
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "héllo".to_string()));
+    assert_eq!(lexer.span(), Span::new(11, 19));
+    assert_eq!(&source[lexer.span().start..lexer.span().end], "\"héllo\"");
+    assert_eq!(lexer.next_token(), Token::Semicolon);
+    assert_eq!(lexer.span(), Span::new(19, 20));
+    assert_eq!(lexer.column(), 18);
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert_eq!(lexer.span(), Span::new(20, 20));
+}
+
+#[test]
+fn tokenization_spans_after_peek() {
+    let source = "x\n  y";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.peek(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(lexer.span(), Span::new(0, 1));
+    assert_eq!(lexer.line(), 1);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(lexer.span(), Span::new(4, 5));
+    assert_eq!(lexer.last_span(), Span::new(0, 1));
+    assert_eq!(lexer.line(), 2);
+    assert_eq!(lexer.column(), 2);
+}
+
+#[test]
+fn tokenization_line_comment() {
+    let source = "x // y / z\ny";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(lexer.line(), 2);
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_nested_block_comment() {
+    let source = "x /* a /* b\n */ c */ y";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(lexer.line(), 2);
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_unterminated_block_comment() {
+    let source = "x /* a /* b */ y";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_doc_comments() {
+    let source = "//! module\n/// outer\n//// plain\nfunc";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::DocComment(DocComment::Inner), " module".to_string()));
+    assert_eq!(next_text(&mut lexer), (Token::DocComment(DocComment::Outer), " outer".to_string()));
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Func));
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_string_escapes() {
+    let source = r#""a\"b\n\t\\\0\x7F\u{1F600}\'""#;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "a\"b\n\t\\\0\x7F\u{1F600}'".to_string()));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_string_invalid_escape() {
+    let source = r#""a\qb\x80" x"#;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "ab".to_string()));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(
+        lexer.errors(),
+        &[
+            LexError::new(LexicalErrors::LEXICAL_INVALID_ESCAPE, Span::new(2, 4)),
+            LexError::new(LexicalErrors::LEXICAL_INVALID_ESCAPE, Span::new(5, 9)),
+        ]
+    );
+}
+
+#[test]
+fn tokenization_unterminated_string() {
+    let source = "x \"abc";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert_eq!(
+        lexer.errors(),
+        &[LexError::new(LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(2, 6))]
+    );
+}
+
+#[test]
+fn tokenization_char_literals() {
+    let source = r"'a' '\n' '\'' '\u{E9}'";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Char('a')));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Char('\n')));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Char('\'')));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Char('é')));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_invalid_char_literals() {
+    let source = "'' 'abc' 'x\ny";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert_eq!(
+        lexer.errors(),
+        &[
+            LexError::new(LexicalErrors::LEXICAL_EMPTY_CHAR, Span::new(0, 2)),
+            LexError::new(LexicalErrors::LEXICAL_MULTIPLE_CHARS, Span::new(3, 8)),
+            LexError::new(LexicalErrors::LEXICAL_UNTERMINATED_CHAR, Span::new(9, 11)),
+        ]
+    );
+}
+
+#[test]
+fn tokenization_identifier_grammar() {
+    let source = "user_id x1 _tmp parseV2 _ naïve 名前";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    for name in ["user_id", "x1", "_tmp", "parseV2", "_", "naïve", "名前"] {
+        assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern(name)));
+    }
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_reserved_words() {
+    let source = Keywords::ALL.map(Keywords::as_str).join(" ");
+
+    let mut lexer = Lexer::tokenization("main", &source);
+    for keyword in Keywords::ALL {
+        assert_eq!(lexer.next_token(), Token::Keyword(keyword), "`{keyword}`");
+    }
+    assert_eq!(lexer.next_token(), Token::EOF);
+
+    let mut lexer = Lexer::tokenization("main", "for_each inner asset mutable pubs");
+    for name in ["for_each", "inner", "asset", "mutable", "pubs"] {
+        assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern(name)));
+    }
+}
+
+#[test]
+fn tokenization_keyword_prefixed_identifiers() {
+    let source = "letter iffy i32x truex let if i32 true";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    for name in ["letter", "iffy", "i32x", "truex"] {
+        assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern(name)));
+    }
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::If));
+    assert_eq!(lexer.next_token(), Token::Type(crate::types::Type::I32));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Boolean(true)));
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_number_radix_and_separators() {
+    let source = "0xFF 0o755 0b1010_0001 1_000_000 18446744073709551615";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(0xFF)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(0o755)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(0b1010_0001)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(1_000_000)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::ULong(u64::MAX)));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_number_suffixes() {
+    let source = "255u8 10i64 2.5f32 1e3 0x1Fu16 7f64 1.5e-3";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::TypedInteger(255, Type::U8)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::TypedInteger(10, Type::I64)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::TypedFloat(2.5, Type::F32)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Float(1e3)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::TypedInteger(0x1F, Type::U16)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::TypedFloat(7.0, Type::F64)));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Float(1.5e-3)));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_invalid_numbers() {
+    let source = "256u8 0b102 18446744073709551616 2.5i32 12abc 0x";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert_eq!(
+        lexer.errors(),
+        &[
+            LexError::new(LexicalErrors::LEXICAL_NUMBER_OVERFLOW, Span::new(0, 5)),
+            LexError::new(LexicalErrors::LEXICAL_INVALID_DIGIT, Span::new(10, 11)),
+            LexError::new(LexicalErrors::LEXICAL_NUMBER_OVERFLOW, Span::new(12, 32)),
+            LexError::new(LexicalErrors::LEXICAL_INVALID_SUFFIX, Span::new(36, 39)),
+            LexError::new(LexicalErrors::LEXICAL_INVALID_SUFFIX, Span::new(42, 45)),
+            LexError::new(LexicalErrors::LEXICAL_MISSING_DIGITS, Span::new(46, 48)),
+        ]
+    );
+}
+
+#[test]
+fn tokenization_minus_is_operator() {
+    let source = "x-1 -128i8 -2.5";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::SUB));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(1)));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::SUB));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::TypedInteger(128, Type::I8)));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::SUB));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Float(2.5)));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_operators() {
+    use Operator::*;
+    let operators = [
+        ADD, SUB, MUL, DIV, MOD, EQUAL, NOTEQ, LT, GT, LTEQ, GTEQ, AND, OR, ADDRESSING, BITOR, XOR, SHL, SHR,
+        NOT, BITNOT, ARROW, NAVIGATION, RANGE, RANGE_INCLUSIVE, QUESTION, FAT_ARROW, ASSIGNMENT,
+        ADD_ASSIGNMENT, SUB_ASSIGNMENT, MUL_ASSIGNMENT, DIV_ASSIGNMENT, MOD_ASSIGNMENT, BITAND_ASSIGNMENT, BITOR_ASSIGNMENT,
+        XOR_ASSIGNMENT, SHL_ASSIGNMENT, SHR_ASSIGNMENT,
+    ];
+    let source = operators.iter().map(|op| op.to_string()).collect::<Vec<_>>().join(" ");
+    assert_eq!(
+        source,
+        "+ - * / % == != < > <= >= && || & | ^ << >> ! ~ -> :: .. ..= ? => = += -= *= /= %= &= |= ^= <<= >>="
+    );
+
+    let mut lexer = Lexer::tokenization("main", &source);
+    for op in operators {
+        assert_eq!(lexer.next_token(), Token::Operator(op));
+    }
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_punctuation() {
+    let tokens = [
+        Token::Dot,
+        Token::Comma,
+        Token::Colon,
+        Token::Semicolon,
+        Token::AtSign,
+        Token::LParen,
+        Token::RParen,
+        Token::LBrace,
+        Token::RBrace,
+        Token::LBracket,
+        Token::RBracket,
+    ];
+    let source = tokens.iter().map(|token| token.to_string()).collect::<Vec<_>>().concat();
+    assert_eq!(source, ".,:;@(){}[]");
+
+    let mut lexer = Lexer::tokenization("main", &source);
+    for token in tokens {
+        assert_eq!(lexer.next_token(), token);
+    }
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_adjacent_operators() {
+    let source = "a[i]<<=1..=n?";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+    assert_eq!(lexer.next_token(), Token::LBracket);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("i")));
+    assert_eq!(lexer.next_token(), Token::RBracket);
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::SHL_ASSIGNMENT));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(1)));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::RANGE_INCLUSIVE));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("n")));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::QUESTION));
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn operator_precedence() {
+    use crate::scanner::Associativity;
+    let binding = |op: Operator| op.precedence().map(|(binding, _)| binding);
+
+    assert!(binding(Operator::MUL) > binding(Operator::ADD));
+    assert!(binding(Operator::ADD) > binding(Operator::SHL));
+    assert!(binding(Operator::EQUAL) > binding(Operator::AND));
+    assert!(binding(Operator::AND) > binding(Operator::OR));
+    assert_eq!(Operator::SUB.precedence().map(|(_, assoc)| assoc), Some(Associativity::Left));
+    assert_eq!(Operator::ASSIGNMENT.precedence().map(|(_, assoc)| assoc), Some(Associativity::Right));
+    assert!(Operator::SHR_ASSIGNMENT.is_assignment());
+    assert_eq!(Operator::NOT.precedence(), None);
+}
+
+#[test]
+fn tokenization_invalid_characters() {
+    let source = "let $x = 1 # 2;";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(
+        lexer.errors(),
+        &[
+            LexError::new(LexicalErrors::LEXICAL_INVALID_CHARACTER, Span::new(4, 5)),
+            LexError::new(LexicalErrors::LEXICAL_INVALID_CHARACTER, Span::new(11, 12)),
+        ]
+    );
+    assert_eq!(lexer.errors()[0].kind.code(), "E0001");
+}
+
+#[test]
+fn tokenization_errors_reported_once() {
+    let source = "/* open \"x";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(
+        lexer.errors(),
+        &[LexError::new(LexicalErrors::LEXICAL_UNTERMINATED_COMMENT, Span::new(0, 10))]
+    );
+}
+
+#[test]
+fn trivia_attachment() {
+    let source = "  x // note\n\ny ";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let tokens = lexer.with_trivia();
+    assert_eq!(tokens.len(), 3);
+
+    assert_eq!(tokens[0].token, Token::Identifier(Symbol::intern("x")));
+    assert_eq!(tokens[0].leading.iter().map(|t| t.kind).collect::<Vec<_>>(), [TriviaKind::Whitespace]);
+    assert_eq!(tokens[0].leading[0].span, Span::new(0, 2));
+    assert_eq!(
+        tokens[0].trailing.iter().map(|t| t.kind).collect::<Vec<_>>(),
+        [TriviaKind::Whitespace, TriviaKind::Comment, TriviaKind::Newline]
+    );
+
+    assert_eq!(tokens[1].token, Token::Identifier(Symbol::intern("y")));
+    assert_eq!(tokens[1].leading.iter().map(|t| t.kind).collect::<Vec<_>>(), [TriviaKind::Newline]);
+    assert_eq!(tokens[1].trailing.iter().map(|t| t.kind).collect::<Vec<_>>(), [TriviaKind::Whitespace]);
+
+    assert_eq!(tokens[2].token, Token::EOF);
+    assert!(tokens[2].leading.is_empty());
+}
+
+#[test]
+fn trivia_round_trip_corpus() {
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut files = 0;
+    for entry in std::fs::read_dir(corpus).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "ex") {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).unwrap();
+
+        let mut lexer = Lexer::tokenization(path.to_str().unwrap(), &source);
+        let rebuilt: String = lexer
+            .with_trivia()
+            .iter()
+            .map(|token| {
+                let span = token.full_span();
+                &source[span.start..span.end]
+            })
+            .collect();
+        assert_eq!(rebuilt, source, "round trip failed for {}", path.display());
+        files += 1;
+    }
+    assert!(files > 0);
+}
+
+#[test]
+fn lexer_iterator() {
+    let source = "let x = x;";
+
+    let tokens: Vec<Token> = Lexer::tokenization("main", source).collect();
+    let x = Token::Identifier(Symbol::intern("x"));
+    assert_eq!(
+        tokens,
+        [Token::Keyword(Keywords::Let), x, Token::Operator(Operator::ASSIGNMENT), x, Token::Semicolon]
+    );
+}
+
+#[test]
+fn symbol_interning() {
+    let a = Symbol::intern("user_id");
+    let b = Symbol::intern(&String::from("user_id"));
+    assert_eq!(a, b);
+    assert_ne!(a, Symbol::intern("user_ids"));
+    assert_eq!(a.as_str(), "user_id");
+    assert_eq!(a.to_string(), "user_id");
+
+    // Resolved on a thread that has not seen the symbol yet.
+    let c = Symbol::intern("interned_elsewhere");
+    assert_eq!(std::thread::spawn(move || c.as_str()).join().unwrap(), "interned_elsewhere");
+    assert_eq!(std::thread::spawn(move || Symbol::intern("interned_elsewhere")).join().unwrap(), c);
+}
+
+#[test]
+fn string_text_is_read_from_the_source() {
+    let source = "\"a\\tb {{c}}\" r#\"d\\n\"# \"e {x} f\" /// g";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let spans: Vec<_> = lexer
+        .tokens()
+        .iter()
+        .filter(|(token, _)| !token.is_trivia() && !matches!(token, Token::Identifier(_)))
+        .map(|&(_, span)| span)
+        .collect();
+    let texts: Vec<_> = spans.into_iter().map(|span| lexer.text(span)).collect();
+    assert_eq!(texts, ["a\tb {c}", "d\\n", "e ", " f", " g"]);
+    assert_eq!(crate::text("\"\"\"\n    h\n    \"\"\""), "h");
+}
+
+#[test]
+fn peek_does_not_move() {
+    let source = "a\n\tb\n\n c";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.peek(), Token::Identifier(Symbol::intern("a")));
+    assert_eq!(lexer.peek_nth(1), Token::Identifier(Symbol::intern("b")));
+    assert_eq!(lexer.peek_nth(2), Token::Identifier(Symbol::intern("c")));
+    assert_eq!(lexer.peek_nth(3), Token::EOF);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("b")));
+    assert_eq!((lexer.line(), lexer.column()), (2, 1));
+    assert_eq!(lexer.peek(), Token::Identifier(Symbol::intern("c")));
+    assert_eq!((lexer.line(), lexer.column()), (2, 1));
+    assert_eq!(lexer.span(), Span::new(3, 4));
+}
+
+#[test]
+fn checkpoint_rewind() {
+    let source = "a\n\tb c";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let start = lexer.checkpoint();
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+    let after_a = lexer.checkpoint();
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("b")));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("c")));
+    assert_eq!(lexer.next_token(), Token::EOF);
+
+    lexer.rewind(after_a);
+    assert_eq!(lexer.checkpoint(), after_a);
+    assert_eq!((lexer.line(), lexer.span()), (1, Span::new(0, 1)));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("b")));
+    assert_eq!((lexer.line(), lexer.column()), (2, 1));
+    assert_eq!(lexer.last_span(), Span::new(0, 1));
+
+    lexer.rewind(start);
+    assert_eq!(lexer.line(), 1);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+}
+
+#[test]
+fn rewind_to_dropped_tokens() {
+    // Long enough that walking to the end drops the first tokens.
+    let source = format!("\"{{ $ {}", "a ".repeat(2000));
+
+    let mut lexer = Lexer::tokenization("main", &source);
+    let start = lexer.checkpoint();
+    assert_eq!(lexer.next_token(), Token::Interpolation(StringPart::Head));
+    assert_eq!(
+        lexer.errors_so_far(),
+        &[LexError::new(LexicalErrors::LEXICAL_INVALID_CHARACTER, Span::new(3, 4))]
+    );
+    while lexer.next_token() != Token::EOF {}
+    let errors = [
+        LexError::new(LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(0, source.len())),
+        LexError::new(LexicalErrors::LEXICAL_INVALID_CHARACTER, Span::new(3, 4)),
+    ];
+    assert_eq!(lexer.errors_so_far(), &errors);
+
+    lexer.rewind(start);
+    assert_eq!(lexer.next_token(), Token::Interpolation(StringPart::Head));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+    assert_eq!(lexer.span(), Span::new(5, 6));
+    while lexer.next_token() != Token::EOF {}
+    assert_eq!(lexer.errors(), &errors);
+    assert_eq!(lexer.tokens(), TokenStream::new(&source).collect::<Vec<_>>());
+}
 
+#[test]
+fn skip_line_stops_at_line_break() {
+    let source = "a b c\nd";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+    lexer.skip_line();
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("d")));
+}
+
+#[test]
+fn line_index_lookup() {
+    let source = "\nlet a\n\nb";
+    let lines = LineIndex::new(source);
+
+    assert_eq!(lines.line_count(), 4);
+    assert_eq!(lines.line(0), 1);
+    assert_eq!(lines.line(1), 2);
+    assert_eq!(lines.line(6), 2);
+    assert_eq!(lines.line(7), 3);
+    assert_eq!(lines.line(8), 4);
+    assert_eq!(lines.line(100), 4);
+    assert_eq!(lines.line_span(1), Some(0..0));
+    assert_eq!(lines.line_span(2), Some(1..6));
+    assert_eq!(lines.line_span(4), Some(8..9));
+    assert_eq!(lines.line_span(0), None);
+    assert_eq!(lines.line_span(5), None);
+}
+
+#[test]
+fn empty_first_line() {
+    let source = "\nfunc";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.get_source_line(1), Some(""));
+    assert_eq!(lexer.get_source_line(2), Some("func"));
+    assert_eq!(lexer.get_source_line(3), None);
+    lexer.next_token();
+    assert_eq!(lexer.location(lexer.span().start), (2, 0));
+}
+
+#[test]
+fn column_units() {
+    let source = "\tlet é = \"😀\" x";
+    let offset = source.find('x').unwrap();
+
+    let lexer = Lexer::tokenization("main", source);
+    let column = |unit| lexer.location_in(offset, unit).column;
+    assert_eq!(column(ColumnUnit::Utf8), 17);
+    assert_eq!(column(ColumnUnit::Char), 13);
+    assert_eq!(column(ColumnUnit::Utf16), 14);
+    assert_eq!(column(ColumnUnit::Display { tab_width: 4 }), 16);
+    assert_eq!(column(ColumnUnit::Display { tab_width: 8 }), 20);
+}
+
+#[test]
+fn column_to_offset() {
+    let source = "a\n\t😀b";
+    let lines = LineIndex::new(source);
+
+    assert_eq!(lines.offset(source, 2, 0, ColumnUnit::Utf16), Some(2));
+    assert_eq!(lines.offset(source, 2, 1, ColumnUnit::Utf16), Some(3));
+    assert_eq!(lines.offset(source, 2, 2, ColumnUnit::Utf16), Some(3));
+    assert_eq!(lines.offset(source, 2, 3, ColumnUnit::Utf16), Some(7));
+    assert_eq!(lines.offset(source, 2, 4, ColumnUnit::Display { tab_width: 4 }), Some(3));
+    assert_eq!(lines.offset(source, 2, 99, ColumnUnit::Char), Some(8));
+    assert_eq!(lines.offset(source, 3, 0, ColumnUnit::Char), None);
+}
+
+#[test]
+fn source_map_files() {
+    let mut map = SourceMap::new();
+    let main = map.add("main.ex", "func main() {\n}\n");
+    let util = map.add("util.ex", "let x = 1;");
+
+    assert_ne!(main, util);
+    assert_eq!(map.get(main).name(), "main.ex");
+    assert_eq!(map.get(util).line_text(1), Some("let x = 1;"));
+    assert_eq!(map.get(main).line_text(3), Some(""));
+    assert_eq!(map.location(main, 14, ColumnUnit::Char), Location { line: 2, column: 0 });
+    assert_eq!(map.location(util, 4, ColumnUnit::Char), Location { line: 1, column: 4 });
+    assert_eq!(map.files().count(), 2);
+
+    let file = map.get(util);
+    let mut lexer = Lexer::tokenization(file.name(), file.source());
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+}
+
+#[test]
+fn tokenization_interpolation() {
+    let source = r#""user {name} has {count + 1} items""#;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Head), "user ".to_string()));
+    assert_eq!(lexer.span(), Span::new(0, 7));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("name")));
+    assert_eq!(lexer.span(), Span::new(7, 11));
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Middle), " has ".to_string()));
+    assert_eq!(lexer.span(), Span::new(11, 18));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("count")));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::ADD));
+    assert_eq!(lexer.next_token(), Token::Literal(Literal::Integer(1)));
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Tail), " items".to_string()));
+    assert_eq!(lexer.span(), Span::new(27, source.len()));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_interpolation_nested() {
+    let source = r#""a {f({x}) + "b {y}"} c" {}"#;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Head), "a ".to_string()));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("f")));
+    assert_eq!(lexer.next_token(), Token::LParen);
+    assert_eq!(lexer.next_token(), Token::LBrace);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::RBrace);
+    assert_eq!(lexer.next_token(), Token::RParen);
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::ADD));
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Head), "b ".to_string()));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Tail), "".to_string()));
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Tail), " c".to_string()));
+    assert_eq!(lexer.next_token(), Token::LBrace);
+    assert_eq!(lexer.next_token(), Token::RBrace);
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_interpolation_escaped_braces() {
+    let source = r#""{{x}} } {y}}}""#;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Head), "{x} } ".to_string()));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(next_text(&mut lexer), (Token::Interpolation(StringPart::Tail), "}".to_string()));
+    assert_eq!(lexer.next_token(), Token::EOF);
+
+    let mut lexer = Lexer::tokenization("main", r#""{{}}""#);
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "{}".to_string()));
+}
+
+#[test]
+fn tokenization_interpolation_unterminated() {
+    let source = "let a = \"x {y";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let errors: Vec<_> = lexer.errors().iter().map(|error| (error.kind, error.span)).collect();
+    assert_eq!(errors, [(LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(8, 13))]);
+
+    let source = "\"x {y} z";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let errors: Vec<_> = lexer.errors().iter().map(|error| (error.kind, error.span)).collect();
+    assert_eq!(errors, [(LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(5, 8))]);
+
+    for (source, end) in [("\"a {b\"", 6), ("\"{\"", 3), ("\"a {\"b {c\" + 1;", 15)] {
+        let mut lexer = Lexer::tokenization("main", source);
+        let errors: Vec<_> = lexer.errors().iter().map(|error| (error.kind, error.span)).collect();
+        assert_eq!(errors, [(LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(0, end))], "{source}");
+    }
+}
+
+#[test]
+fn tokenization_raw_string() {
+    let source = r####"r"C:\path\{x}" r#"say "hi""# r##"a "# b"## r"" r"####;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), r"C:\path\{x}".to_string()));
+    assert_eq!(lexer.span(), Span::new(0, 14));
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), r#"say "hi""#.to_string()));
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), r##"a "# b"##.to_string()));
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "".to_string()));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("r")));
+    assert_eq!(lexer.next_token(), Token::EOF);
+    assert!(lexer.errors().is_empty());
+}
+
+#[test]
+fn tokenization_raw_string_unterminated() {
+    let source = r##"r#"abc" x"##;
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let errors: Vec<_> = lexer.errors().iter().map(|error| (error.kind, error.span)).collect();
+    assert_eq!(errors, [(LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(0, source.len()))]);
+}
+
+#[test]
+fn tokenization_text_block() {
+    let source = "let q = \"\"\"\n        SELECT *\n          FROM users\n\n        WHERE id = {id}\\t\n        \"\"\";";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    lexer.skip_token();
+    lexer.skip_token();
+    lexer.skip_token();
+    let expected = "SELECT *\n  FROM users\n\nWHERE id = {id}\t";
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), expected.to_string()));
+    assert_eq!(lexer.span(), Span::new(8, source.len() - 1));
+    assert_eq!(lexer.next_token(), Token::Semicolon);
+    assert_eq!(lexer.line(), 6);
+}
+
+#[test]
+fn tokenization_text_block_indentation() {
+    let source = "\"\"\"\n    a\n  b\n    \"\"\" \"\"\"\n    a\n      \"\"\" \"\"\"x \"y\"\"\"";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "  a\nb".to_string()));
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "a".to_string()));
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "x \"y".to_string()));
+    assert_eq!(lexer.next_token(), Token::EOF);
+}
+
+#[test]
+fn tokenization_text_block_errors() {
+    let source = "\"\"\"\n  a\\q\n  \"\"\" \"\"\"b";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let errors: Vec<_> = lexer.errors().iter().map(|error| (error.kind, error.span)).collect();
+    assert_eq!(
+        errors,
+        [
+            (LexicalErrors::LEXICAL_INVALID_ESCAPE, Span::new(7, 9)),
+            (LexicalErrors::LEXICAL_UNTERMINATED_STRING, Span::new(16, source.len())),
+        ]
+    );
+}
+
+/// Applies `edit` incrementally and checks the result against lexing the
+/// edited source from scratch.
+fn relex(source: &str, edit: TextEdit) -> ChangedTokens {
+    let mut edited = source.to_string();
+    edited.replace_range(edit.range.start..edit.range.end, &edit.new_text);
+    let mut fresh = Lexer::tokenization("main", &edited);
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let changed = lexer.apply_edit(&edit);
+    assert_eq!(lexer.source(), edited);
+    assert_eq!(lexer.tokens(), fresh.tokens(), "edit {edit:?} of {source:?}");
+    assert_eq!(lexer.errors(), fresh.errors(), "edit {edit:?} of {source:?}");
+    assert_eq!(lexer.warnings(), fresh.warnings(), "edit {edit:?} of {source:?}");
+    assert_eq!(lexer.get_source_line(2), fresh.get_source_line(2));
+    changed
+}
+
+#[test]
+fn incremental_edit_reuses_tokens() {
+    let source = "let x = 1;\nlet y = 2;\n";
+
+    let changed = relex(source, TextEdit::new(Span::new(4, 5), "count"));
+    assert_eq!(changed, ChangedTokens { removed: 2..3, inserted: 2..3 });
+
+    let changed = relex(source, TextEdit::new(Span::new(9, 9), " + 1"));
+    assert_eq!(changed, ChangedTokens { removed: 6..7, inserted: 6..11 });
+
+    let mut lexer = Lexer::tokenization("main", source);
+    lexer.apply_edit(&TextEdit::new(Span::new(15, 16), "total"));
+    assert_eq!(lexer.tokens()[11], (Token::Identifier(Symbol::intern("total")), Span::new(15, 20)));
+    assert_eq!(lexer.tokens()[16], (Token::Semicolon, Span::new(24, 25)));
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+}
+
+#[test]
+fn incremental_edit_merges_and_splits_tokens() {
+    let source = "a b = c";
+
+    let changed = relex(source, TextEdit::new(Span::new(1, 2), ""));
+    assert_eq!(changed, ChangedTokens { removed: 0..3, inserted: 0..1 });
+    relex("a  b c", TextEdit::new(Span::new(3, 4), ""));
+    relex("a  b c", TextEdit::new(Span::new(3, 3), "\t"));
+    relex(source, TextEdit::new(Span::new(5, 5), "="));
+    relex(source, TextEdit::new(Span::new(3, 3), "<"));
+    relex("r #\"x\"#", TextEdit::new(Span::new(1, 2), ""));
+}
+
+#[test]
+fn incremental_edit_relexes_to_the_end() {
+    let source = "let a = 1;\nlet b = \"x\";\n$";
+
+    let changed = relex(source, TextEdit::new(Span::new(8, 8), "\""));
+    assert_eq!(changed.removed, 6..19);
+    relex(source, TextEdit::new(Span::new(8, 8), "/*"));
+    relex(source, TextEdit::new(Span::new(0, source.len()), ""));
+    relex("", TextEdit::new(Span::new(0, 0), "func"));
+}
+
+#[test]
+fn incremental_edit_inside_interpolation() {
+    let source = "let s = \"a {x + 1} b {y}\";\nlet t = 2;";
+
+    relex(source, TextEdit::new(Span::new(12, 13), "name"));
+    relex(source, TextEdit::new(Span::new(17, 18), ""));
+    relex(source, TextEdit::new(Span::new(13, 13), "{"));
+    relex(source, TextEdit::new(Span::new(8, 9), ""));
+    relex(source, TextEdit::new(Span::new(24, 25), ""));
+}
+
+#[test]
+fn incremental_edit_corpus() {
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    for entry in std::fs::read_dir(corpus).unwrap() {
+        let source = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        let boundaries = source.char_indices().map(|(i, _)| i).step_by(3);
+        for start in boundaries {
+            let end = start + source[start..].chars().next().map_or(0, char::len_utf8);
+            relex(&source, TextEdit::new(Span::new(start, end), ""));
+            for text in ["\"", "{", "}", "x", " ", "/*", "\n"] {
+                relex(&source, TextEdit::new(Span::new(start, start), text));
+            }
+        }
+    }
+}
+
+#[test]
+fn tokenization_crlf_and_lone_cr() {
+    let source = "let a\r\n  b\rc /* x\r\ny */ d\n\r e";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("a")));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("b")));
+    assert_eq!((lexer.line(), lexer.column()), (2, 2));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("c")));
+    assert_eq!((lexer.line(), lexer.column()), (3, 0));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("d")));
+    assert_eq!((lexer.line(), lexer.column()), (4, 5));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("e")));
+    assert_eq!((lexer.line(), lexer.column()), (6, 1));
+    assert_eq!(lexer.location(lexer.span().start), (6, 1));
+    assert_eq!(lexer.get_source_line(1), Some("let a"));
+    assert_eq!(lexer.get_source_line(2), Some("  b"));
+    assert_eq!(lexer.get_source_line(5), Some(""));
+    assert!(lexer.errors().is_empty());
+
+    let kinds: Vec<_> = lexer.with_trivia()[1].trailing.iter().map(|trivia| (trivia.kind, trivia.span)).collect();
+    assert_eq!(kinds, [(TriviaKind::Newline, Span::new(5, 7))]);
+}
+
+#[test]
+fn tokenization_comments_end_at_cr() {
+    let source = "// note\rx /// doc\r\ny";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(next_text(&mut lexer), (Token::DocComment(DocComment::Outer), " doc".to_string()));
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("y")));
+    assert_eq!(lexer.line(), 3);
+}
+
+#[test]
+fn tokenization_text_block_crlf() {
+    let source = "\"\"\"\r\n    a\r\n\r\n    b\r\n    \"\"\"";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(next_text(&mut lexer), (Token::Literal(Literal::String), "a\n\nb".to_string()));
+    assert_eq!(lexer.line(), 5);
+}
+
+#[test]
+fn tokenization_bom_and_shebang() {
+    let source = "\u{FEFF}#!/usr/bin/env exodus\r\nfunc main";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Func));
+    assert_eq!((lexer.line(), lexer.column()), (2, 0));
+    assert_eq!(lexer.get_source_line(1), Some("#!/usr/bin/env exodus"));
+    assert!(lexer.errors().is_empty());
+
+    let mut lexer = Lexer::tokenization("main", "\u{FEFF}let x");
+    assert_eq!(lexer.next_token(), Token::Keyword(Keywords::Let));
+    assert_eq!(lexer.span(), Span::new(3, 6));
+    assert_eq!((lexer.line(), lexer.column()), (1, 0));
+    assert_eq!(lexer.location_in(0, ColumnUnit::Utf16), Location { line: 1, column: 0 });
+    assert_eq!(lexer.get_source_line(1), Some("let x"));
+
+    let mut lexer = Lexer::tokenization("main", "#!x");
+    assert_eq!(lexer.next_token(), Token::EOF);
+
+    let mut lexer = Lexer::tokenization("main", "x\n#!y");
+    assert_eq!(lexer.next_token(), Token::Identifier(Symbol::intern("x")));
+    assert_eq!(lexer.next_token(), Token::Operator(Operator::NOT));
+    assert_eq!(lexer.errors()[0].kind, LexicalErrors::LEXICAL_INVALID_CHARACTER);
+}
+
+#[test]
+fn incremental_edit_bom_and_shebang() {
+    let source = "\u{FEFF}#!/bin/exodus\nfunc";
+
+    relex(source, TextEdit::new(Span::new(3, 3), " "));
+    relex(source, TextEdit::new(Span::new(3, 4), ""));
+    relex(source, TextEdit::new(Span::new(0, 3), ""));
+    relex("\u{FEFF}x", TextEdit::new(Span::new(3, 3), "#!"));
+    relex("x\r\ny", TextEdit::new(Span::new(2, 3), ""));
+}
+
+#[test]
+fn token_kinds() {
+    let source = "x 1 let i32 ; \"a{b}\" + //c\n";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let kinds: Vec<_> = lexer.tokens().iter().map(|(token, _)| token.kind()).filter(|kind| *kind != TokenKind::Space).collect();
+    assert_eq!(
+        kinds,
+        [
+            TokenKind::Identifier,
+            TokenKind::Literal,
+            TokenKind::Keyword,
+            TokenKind::Type,
+            TokenKind::Semicolon,
+            TokenKind::Interpolation,
+            TokenKind::Identifier,
+            TokenKind::Interpolation,
+            TokenKind::Operator,
+            TokenKind::Comment,
+            TokenKind::Line,
+        ]
+    );
+    assert_eq!(Token::EOF.kind(), TokenKind::EOF);
+}
+
+#[test]
+fn token_sets() {
+    const END: TokenSet = TokenSet![Semicolon, RBrace];
+
+    assert!(END.contains(TokenKind::Semicolon));
+    assert!(END.contains(Token::RBrace.kind()));
+    assert!(!END.contains(TokenKind::LBrace));
+    assert_eq!(END.len(), 2);
+    assert_eq!(END, TokenSet![RBrace, Semicolon,]);
+    assert_eq!(END.with(TokenKind::EOF), END.union(TokenSet![EOF]));
+    assert!(TokenSet![].is_empty());
+    assert_eq!(TokenSet::EMPTY, TokenSet::default());
+    assert_eq!(END.iter().collect::<Vec<_>>(), [TokenKind::Semicolon, TokenKind::RBrace]);
+    assert_eq!(format!("{END:?}"), "{Semicolon, RBrace}");
+
+    let all = TokenKind::ALL.iter().fold(TokenSet::EMPTY, |set, kind| set.with(*kind));
+    assert_eq!(all.len(), TokenKind::ALL.len());
+}
+
+#[test]
+fn token_set_messages() {
+    assert_eq!(TokenSet![Semicolon, RBrace].to_string(), "one of: `;`, `}`");
+    assert_eq!(TokenSet![Comma, RParen, Identifier].to_string(), "one of: identifier, `,`, `)`");
+    assert_eq!(TokenSet![LBrace].to_string(), "`{`");
+    assert_eq!(TokenSet![].to_string(), "nothing");
+}
+
+#[test]
+fn warnings_bidi_characters() {
+    let source = "let s = \"ok\u{202E} \u{2066}\";\n// \u{202D}x\n/// \u{2069}\nlet t = \"{s}\u{202B}\";\nlet u = '\u{202A}';\n";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    assert!(lexer.errors().is_empty());
+    let spans: Vec<_> = lexer.warnings().iter().map(|warning| warning.span).collect();
+    let expected: Vec<_> = source
+        .char_indices()
+        .filter(|(_, c)| unicode::is_bidi_control(*c))
+        .map(|(i, c)| Span::new(i, i + c.len_utf8()))
+        .collect();
+    assert_eq!(spans.len(), 6);
+    assert_eq!(spans, expected);
+    assert!(lexer.warnings().iter().all(|warning| warning.kind == LexicalWarnings::LEXICAL_BIDI_CHARACTER));
+    assert_eq!(LexicalWarnings::LEXICAL_BIDI_CHARACTER.code(), "W0001");
+}
+
+#[test]
+fn warnings_confusable_identifiers() {
+    let source = "let раураl = 1;\nlet pаypal = 2;\nlet привет = 3;\nlet café = 4;\nlet ｍａｉｎ = 5;\nlet plain = 6;\n";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let warned: Vec<_> = lexer.warnings().iter().map(|warning| &source[warning.span.start..warning.span.end]).collect();
+    assert_eq!(warned, ["раураl", "pаypal", "ｍａｉｎ"]);
+    assert_eq!(
+        lexer.warnings()[0],
+        LexWarning::new(LexicalWarnings::LEXICAL_CONFUSABLE_IDENTIFIER, Span::new(4, 15))
+    );
+
+    assert_eq!(unicode::skeleton("раураl").as_deref(), Some("paypal"));
+    assert_eq!(unicode::skeleton("ｍａｉｎ＿１").as_deref(), Some("main_1"));
+    assert_eq!(unicode::skeleton("привет"), None);
+    assert_eq!(unicode::skeleton("plain"), None);
+    // Greek, Cherokee and Armenian spoofs, with no ASCII in them at all.
+    assert_eq!(unicode::skeleton("ραρα").as_deref(), Some("papa"));
+    assert_eq!(unicode::skeleton("ιοτα"), None);
+    assert_eq!(unicode::skeleton("ᏴᎪᎠ").as_deref(), Some("BAD"));
+    assert_eq!(unicode::skeleton("ՍՏ").as_deref(), Some("US"));
+    assert_eq!(unicode::lookalike('І'), Some('I'));
+    assert_eq!(unicode::lookalike('ｍ'), Some('m'));
+    assert_eq!(unicode::lookalike('ɡ'), Some('g'));
+    assert_eq!(unicode::lookalike('ж'), None);
+}
+
+#[test]
+fn incremental_edit_updates_warnings() {
+    let source = "let a = \"x\";\nlet b = 1;\n";
+
+    relex(source, TextEdit::new(Span::new(10, 10), "\u{202E}"));
+    relex(source, TextEdit::new(Span::new(18, 19), "с"));
+    relex("let с = 1;\n", TextEdit::new(Span::new(4, 6), "c"));
+}
+
+#[test]
+fn token_stream_matches_lexer() {
+    let source = "\u{FEFF}let  s = \"a {x} b\";\t\t// c\r\n$ 1u9";
+
+    let mut lexer = Lexer::tokenization("main", source);
+    let mut stream = TokenStream::new(source);
+    let tokens: Vec<_> = stream.by_ref().collect();
+    assert_eq!(tokens, lexer.tokens());
+    assert_eq!(stream.finish(), lexer.errors());
+    assert_eq!(lexer.tokens()[2], (Token::Space, Span::new(6, 8)));
 }
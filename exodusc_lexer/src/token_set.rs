@@ -0,0 +1,73 @@
+use crate::scanner::TokenKind;
+
+/// A set of [`TokenKind`]s packed into a single word, usually built with the
+/// [`TokenSet!`](crate::TokenSet!) macro.
+///
+/// Its `Display` reads as the tail of an "expected ..." diagnostic: a lone
+/// kind prints as itself, several as "one of: `;`, `}`".
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Default)]
+pub struct TokenSet(u64);
+
+impl TokenSet {
+    pub const EMPTY: TokenSet = TokenSet(0);
+
+    pub const fn new(kinds: &[TokenKind]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < kinds.len() {
+            bits |= 1 << kinds[i] as u64;
+            i += 1;
+        }
+        TokenSet(bits)
+    }
+
+    pub const fn contains(&self, kind: TokenKind) -> bool {
+        self.0 & (1 << kind as u64) != 0
+    }
+
+    pub const fn with(self, kind: TokenKind) -> Self {
+        TokenSet(self.0 | 1 << kind as u64)
+    }
+
+    pub const fn union(self, other: TokenSet) -> Self {
+        TokenSet(self.0 | other.0)
+    }
+
+    pub const fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The kinds in the set, in declaration order.
+    pub fn iter(&self) -> impl Iterator<Item = TokenKind> + '_ {
+        TokenKind::ALL.into_iter().filter(|kind| self.contains(*kind))
+    }
+}
+
+impl std::fmt::Debug for TokenSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl std::fmt::Display for TokenSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kinds: Vec<_> = self.iter().map(|kind| kind.to_string()).collect();
+        match kinds.len() {
+            0 => write!(f, "nothing"),
+            1 => write!(f, "{}", kinds[0]),
+            _ => write!(f, "one of: {}", kinds.join(", ")),
+        }
+    }
+}
+
+/// Builds a [`TokenSet`] from [`TokenKind`] names: `TokenSet![Semicolon, RBrace]`.
+#[macro_export]
+macro_rules! TokenSet {
+    ($($kind:ident),* $(,)?) => {
+        $crate::token_set::TokenSet::new(&[$($crate::scanner::TokenKind::$kind),*])
+    };
+}
@@ -0,0 +1,84 @@
+use crate::{scanner::Token, span::Span};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TriviaKind {
+    Whitespace,
+    Newline,
+    Comment,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+/// A token together with the whitespace, newlines and comments around it.
+///
+/// Trailing trivia runs up to and including the first newline after the
+/// token; everything after that leads the next token. The stream always ends
+/// with an empty [`Token::EOF`] that owns whatever trivia is left, so joining
+/// every [`TriviaToken::full_span`] rebuilds the source byte-for-byte.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TriviaToken {
+    pub token: Token,
+    pub span: Span,
+    pub leading: Vec<Trivia>,
+    pub trailing: Vec<Trivia>,
+}
+
+impl TriviaToken {
+    /// Span covering the token and all of its trivia.
+    pub fn full_span(&self) -> Span {
+        let start = self.leading.first().map_or(self.span.start, |trivia| trivia.span.start);
+        let end = self.trailing.last().map_or(self.span.end, |trivia| trivia.span.end);
+        Span::new(start, end)
+    }
+}
+
+fn kind(token: &Token) -> Option<TriviaKind> {
+    match token {
+        Token::Space => Some(TriviaKind::Whitespace),
+        Token::Line => Some(TriviaKind::Newline),
+        Token::Comment => Some(TriviaKind::Comment),
+        _ => None,
+    }
+}
+
+pub(crate) fn attach(data: &[(Token, Span)], source_len: usize) -> Vec<TriviaToken> {
+    let mut tokens: Vec<TriviaToken> = Vec::new();
+    let mut leading = Vec::new();
+    let mut trailing_open = false;
+
+    for (token, span) in data {
+        match kind(token) {
+            Some(kind) => {
+                let trivia = Trivia { kind, span: *span };
+                match tokens.last_mut() {
+                    Some(last) if trailing_open => {
+                        last.trailing.push(trivia);
+                        trailing_open = kind != TriviaKind::Newline;
+                    }
+                    _ => leading.push(trivia),
+                }
+            }
+            None => {
+                tokens.push(TriviaToken {
+                    token: *token,
+                    span: *span,
+                    leading: std::mem::take(&mut leading),
+                    trailing: Vec::new(),
+                });
+                trailing_open = true;
+            }
+        }
+    }
+
+    tokens.push(TriviaToken {
+        token: Token::EOF,
+        span: Span::new(source_len, source_len),
+        leading,
+        trailing: Vec::new(),
+    });
+    tokens
+}
@@ -1,4 +1,6 @@
-#[derive(PartialEq, Debug, Clone)]
+use crate::symbol::Symbol;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum Type {
     Void,
     I8,
@@ -14,7 +16,7 @@ pub enum Type {
     Boolean,
     Char,
     String,
-    Object(String),
+    Object(Symbol),
 }
 
 impl std::fmt::Display for Type {
@@ -41,6 +43,51 @@ impl std::fmt::Display for Type {
 
 impl Type {
     pub fn is_any(&self) -> bool {
-        return self == &Type::Void;
+        self == &Type::Void
+    }
+
+    /// Looks up a builtin type by its source name, e.g. `i32` or `bool`.
+    pub fn primitive(name: &str) -> Option<Type> {
+        match name {
+            "i8" => Some(Type::I8),
+            "i16" => Some(Type::I16),
+            "i32" => Some(Type::I32),
+            "i64" => Some(Type::I64),
+            "u8" => Some(Type::U8),
+            "u16" => Some(Type::U16),
+            "u32" => Some(Type::U32),
+            "u64" => Some(Type::U64),
+            "f32" => Some(Type::F32),
+            "f64" => Some(Type::F64),
+            "char" => Some(Type::Char),
+            "string" => Some(Type::String),
+            "bool" => Some(Type::Boolean),
+            _ => None,
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.integer_limit(false).is_some()
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Type::F32 | Type::F64)
+    }
+
+    /// Largest magnitude an integer of this type can hold, on the negative
+    /// side when `negative` is set. `None` for non-integer types.
+    pub fn integer_limit(&self, negative: bool) -> Option<u64> {
+        let (max, min) = match self {
+            Type::I8 => (i8::MAX as u64, i8::MIN.unsigned_abs() as u64),
+            Type::I16 => (i16::MAX as u64, i16::MIN.unsigned_abs() as u64),
+            Type::I32 => (i32::MAX as u64, i32::MIN.unsigned_abs() as u64),
+            Type::I64 => (i64::MAX as u64, i64::MIN.unsigned_abs()),
+            Type::U8 => (u8::MAX as u64, 0),
+            Type::U16 => (u16::MAX as u64, 0),
+            Type::U32 => (u32::MAX as u64, 0),
+            Type::U64 => (u64::MAX, 0),
+            _ => return None,
+        };
+        Some(if negative { min } else { max })
     }
 }
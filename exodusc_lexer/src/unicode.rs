@@ -0,0 +1,96 @@
+//! Detection of source text that reads differently than it compiles, as in
+//! the "Trojan Source" attacks: bidirectional overrides hidden in strings and
+//! comments, and identifiers spelled with look-alikes of ASCII letters.
+
+use crate::{
+    errors::{LexWarning, LexicalWarnings},
+    scanner::Token,
+    span::Span,
+};
+use std::sync::OnceLock;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::confusable_detection;
+
+/// Embeddings and overrides (U+202A–U+202E) and isolates (U+2066–U+2069),
+/// which reorder how the surrounding text is displayed.
+pub fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// The ASCII character `c` is commonly mistaken for, if any.
+///
+/// Look-alikes come from the confusables data of Unicode Technical Standard
+/// #39, applied after NFKC so fullwidth forms count too: `c` is mistaken for
+/// an ASCII letter, digit or `_` with the same UTS #39 prototype, of the same
+/// case where there is a choice. The judgement is one character at a time
+/// and only as good as that data, which for instance maps Greek `κ` to `ĸ`
+/// rather than `k`.
+pub fn lookalike(c: char) -> Option<char> {
+    if c.is_ascii() {
+        return None;
+    }
+    let normalized: String = std::iter::once(c).nfkc().collect();
+    if let [ascii] = normalized.as_bytes() {
+        return is_ident_ascii(char::from(*ascii)).then_some(char::from(*ascii));
+    }
+    let prototype: String = confusable_detection::skeleton(&normalized).collect();
+    ascii_prototypes()
+        .iter()
+        .filter(|(_, ascii_prototype)| *ascii_prototype == prototype)
+        .map(|&(ascii, _)| ascii)
+        .min_by_key(|ascii| (ascii.is_uppercase() != c.is_uppercase(), !ascii.is_alphabetic()))
+}
+
+fn is_ident_ascii(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Every ASCII identifier character with its UTS #39 prototype.
+fn ascii_prototypes() -> &'static [(char, String)] {
+    static PROTOTYPES: OnceLock<Vec<(char, String)>> = OnceLock::new();
+    PROTOTYPES.get_or_init(|| {
+        (0..128u8)
+            .map(char::from)
+            .filter(|&c| is_ident_ascii(c))
+            .map(|c| (c, confusable_detection::skeleton(c.encode_utf8(&mut [0; 4])).collect()))
+            .collect()
+    })
+}
+
+/// The ASCII identifier `ident` would be mistaken for, when it is not ASCII
+/// itself but every other character in it has an ASCII look-alike.
+pub fn skeleton(ident: &str) -> Option<String> {
+    if ident.is_ascii() {
+        return None;
+    }
+    ident.chars().map(|c| if c.is_ascii() { Some(c) } else { lookalike(c) }).collect()
+}
+
+/// Warnings for every bidirectional control character inside a literal or
+/// comment, and every identifier with a [`skeleton`].
+pub(crate) fn check(data: &[(Token, Span)], source: &str) -> Vec<LexWarning> {
+    let mut warnings = Vec::new();
+    let (Some((_, first)), Some((_, last))) = (data.first(), data.last()) else {
+        return warnings;
+    };
+    // Both kinds of warning need a non-ASCII character.
+    if source[first.start..last.end].is_ascii() {
+        return warnings;
+    }
+    for (token, span) in data {
+        match token {
+            Token::Literal(_) | Token::Interpolation(_) | Token::Comment | Token::DocComment(_) => {
+                let text = &source[span.start..span.end];
+                for (i, c) in text.char_indices().filter(|(_, c)| is_bidi_control(*c)) {
+                    let span = Span::new(span.start + i, span.start + i + c.len_utf8());
+                    warnings.push(LexWarning::new(LexicalWarnings::LEXICAL_BIDI_CHARACTER, span));
+                }
+            }
+            Token::Identifier(_) if skeleton(&source[span.start..span.end]).is_some() => {
+                warnings.push(LexWarning::new(LexicalWarnings::LEXICAL_CONFUSABLE_IDENTIFIER, *span));
+            }
+            _ => (),
+        }
+    }
+    warnings
+}
@@ -0,0 +1,12 @@
+func count(limit: i32): i32 {
+	let total = 0;
+	while (total < limit) {
+		let total = total + 1;
+	}
+
+	if (total >= limit) {
+		return total;
+	} else {
+		return limit;
+	}
+}
@@ -0,0 +1,8 @@
+func broken() {
+    let a = 1 $ 2;
+    let b = "unterminated \q escape";
+    let c = 'ab';
+    let d = 0b102;
+    let e = 256u8;   
+}
+/* never closed
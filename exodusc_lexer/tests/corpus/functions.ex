@@ -0,0 +1,22 @@
+//! Small arithmetic helpers.
+
+/// Adds two numbers.
+func add(a: i32, b: i32): i32 {
+    return a + b;
+}
+
+/// Subtracts `b` from `a`.
+///
+/// Negative results are fine.
+func sub(a: i32, b: i32): i32 {
+    return a - b; // trailing comment
+}
+
+func main() {
+    let x: i32 = 10;
+    let y = 0xFF;
+    let mask = 0b1010_0001u8;
+    let big = 18446744073709551615;
+    let neg = -128i8;
+    let ratio = 2.5f32;
+}
@@ -0,0 +1,5 @@
+func spaces()   {  
+
+
+   let x = 1 ;	
+}
\ No newline at end of file
@@ -0,0 +1,4 @@
+#!/usr/bin/env exodus
+func main() {
+    return 0;
+}
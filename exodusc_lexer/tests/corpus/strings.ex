@@ -0,0 +1,17 @@
+/* Literal handling:
+   escapes, unicode and chars. /* nested */ still a comment */
+func greet(name: string): string {
+    let quote = "she said \"hi\"\n";
+    let tab = "a\tb";
+    let emoji = "\u{1F600} ok";
+    let accent = "héllo wörld";
+    let c = '\'';
+    let nl = '\n';
+    let greeting = "hi {name}, {{braces}}";
+    let pattern = r#"^"\d+"$"#;
+    let query = """
+        SELECT *
+          FROM users
+        """;
+    return quote;
+}
@@ -0,0 +1,7 @@
+﻿/// Saved on Windows.
+func main(): i32 {
+    let greeting = """
+        hello
+        """;
+    // old Mac line ending follows    return 0;
+}
@@ -0,0 +1,116 @@
+use colored::Colorize;
+use exodusc_lexer::{
+    errors::{LexError, LexWarning},
+    source_map::{ColumnUnit, FileId, Location, SourceMap},
+    span::Span,
+    unicode,
+};
+
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in the source. `span` is a byte range of `file`.
+/// Nothing is printed when it is created; see [`Diagnostic::render`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    pub file: FileId,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(file: FileId, code: &'static str, message: String, span: Span) -> Self {
+        Self { severity: Severity::Error, code, message, file, span }
+    }
+
+    pub fn warning(file: FileId, code: &'static str, message: String, span: Span) -> Self {
+        Self { severity: Severity::Warning, code, message, file, span }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// The diagnostic as printed to a terminal: where it is, the source line
+    /// with a caret under the start of the span, and the message. `map` must
+    /// hold the text of the file as it was parsed.
+    pub fn render(&self, map: &SourceMap) -> String {
+        let file = map.get(self.file);
+        let Location { line: lines, column: column_number } = file.location(self.span.start, ColumnUnit::Char);
+        let caret = file.location(self.span.start, ColumnUnit::Display { tab_width: TAB_WIDTH }).column;
+        let max_digits = (lines as f64).log10().floor() as usize + 1;
+        let padded_number = format!("{:>0width$}", lines, width=max_digits);
+
+        let source = expand_tabs(file.line_text(lines).unwrap_or_default());
+        let space = " ".repeat(lines.to_string().len());
+        let (color, marker) = match self.severity {
+            Severity::Error => ("\x1b[91m", self.code.bright_red().bold()),
+            Severity::Warning => ("\x1b[93m", self.code.bright_yellow().bold()),
+        };
+
+        let mut rendered = format!("{space}--> {}:{}:{}\n",
+                 file.name().bold().blue(),
+                 padded_number.bright_white(),
+                 column_number.to_string().bright_white()
+        );
+        rendered += &format!("{space}|\n");
+        rendered += &format!("{number}| {source}\n", number = padded_number.bright_cyan(), source = source.trim_end().bright_red());
+        rendered += &format!("{space}|{arrow}{color}^\x1b[0m\n", arrow = " ".repeat(caret + 1));
+        rendered += &format!("{space}|{arrow}{color}|\x1b[0m\n", arrow = " ".repeat(caret + 1));
+        rendered += &format!("{space}| [{marker}]{color} {}: {}\x1b[0m", self.severity, self.message);
+        rendered
+    }
+}
+
+impl Diagnostic {
+    pub fn from_lex_error(file: FileId, error: &LexError) -> Self {
+        Diagnostic::error(file, error.kind.code(), error.kind.to_string(), error.span)
+    }
+
+    /// A lexer warning, naming the ASCII identifier a confusable one looks like.
+    pub fn from_lex_warning(file: FileId, warning: &LexWarning, source: &str) -> Self {
+        let text = &source[warning.span.start..warning.span.end];
+        let message = match unicode::skeleton(text) {
+            Some(skeleton) => format!("{}: `{text}` looks like `{skeleton}`", warning.kind),
+            None => warning.kind.to_string(),
+        };
+        Diagnostic::warning(file, warning.kind.code(), message, warning.span)
+    }
+}
+
+/// Replaces tabs with spaces so the caret under a source line lines up, and
+/// bidirectional control characters with `\u{FFFD}` so they cannot reorder it.
+fn expand_tabs(line: &str) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+    for c in line.chars() {
+        if c == '\t' {
+            let width = TAB_WIDTH - column % TAB_WIDTH;
+            expanded.push_str(&" ".repeat(width));
+            column += width;
+        } else if unicode::is_bidi_control(c) {
+            expanded.push('\u{FFFD}');
+            column += 1;
+        } else {
+            expanded.push(c);
+            column += 1;
+        }
+    }
+    expanded
+}
@@ -8,10 +8,14 @@ pub enum SyntaxErrors {
     SYNTAX_MISSING_TYPE,
     SYNTAX_EXPECTED_EXPRS,
     SYNTAX_EXPECTED_VALUE,
+    SYNTAX_LITERAL_OUT_OF_RANGE,
+    SYNTAX_RESERVED_WORD,
+    SYNTAX_EXPECTED_CALL,
+    SYNTAX_NESTING_TOO_DEEP,
 }
 
 impl SyntaxErrors {
-    pub fn code(&self) -> &str {
+    pub fn code(&self) -> &'static str {
         match self {
             SyntaxErrors::SYNTAX_EXPECTED_NAME => "E0100",
             SyntaxErrors::SYNTAX_EXPECTED_TOKEN => "E0101",
@@ -20,6 +24,25 @@ impl SyntaxErrors {
             SyntaxErrors::SYNTAX_MISSING_TYPE => "E0104",
             SyntaxErrors::SYNTAX_EXPECTED_EXPRS => "E0105",
             SyntaxErrors::SYNTAX_EXPECTED_VALUE => "E0106",
+            SyntaxErrors::SYNTAX_LITERAL_OUT_OF_RANGE => "E0107",
+            SyntaxErrors::SYNTAX_RESERVED_WORD => "E0108",
+            SyntaxErrors::SYNTAX_EXPECTED_CALL => "E0109",
+            SyntaxErrors::SYNTAX_NESTING_TOO_DEEP => "E0110",
+        }
+    }
+}
+
+/// Suspicious but valid source, reported without stopping compilation.
+#[derive(Debug)]
+#[allow(non_camel_case_types)]
+pub enum SyntaxWarnings {
+    SYNTAX_UNATTACHED_DOC_COMMENT,
+}
+
+impl SyntaxWarnings {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SyntaxWarnings::SYNTAX_UNATTACHED_DOC_COMMENT => "W0100",
         }
     }
 }
@@ -1,14 +1,69 @@
-use exodusc_lexer::scanner::{Operator, Literal};
+use exodusc_lexer::{scanner::{Operator, Literal}, span::Span};
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum Expression {
-    Unary {
-        operator: Option<Operator>,
+    /// A literal or a variable name.
+    Literal {
         value: Literal,
+        span: Span,
+    },
+    /// A prefix operator: `-` ([`Operator::SUB`]), `!` ([`Operator::NOT`]),
+    /// `&` ([`Operator::ADDRESSING`]) or `*` ([`Operator::MUL`]), which
+    /// dereferences. The span runs from the operator through the operand.
+    Unary {
+        op: Operator,
+        operand: Box<Expression>,
+        span: Span,
     },
+    /// `(expr)`, spanning both parentheses.
+    Group {
+        expr: Box<Expression>,
+        span: Span,
+    },
+    /// `callee(args)`, spanning through the closing parenthesis. The callee
+    /// is any operand, so `f(x)(y)` calls the result of `f(x)`.
+    Call {
+        callee: Box<Expression>,
+        args: Vec<Expression>,
+        span: Span,
+    },
+    /// `lhs op rhs`; operands bind according to [`Operator::precedence`].
     Binary {
-        x: Literal,
-        operator: Operator,
-        y: Literal,
+        lhs: Box<Expression>,
+        op: Operator,
+        rhs: Box<Expression>,
+    },
+    /// `"user {name} has {count + 1} items"`, spanning both quotes.
+    Interpolation {
+        parts: Vec<InterpolationPart>,
+        span: Span,
+    },
+}
+
+impl Expression {
+    /// Where the expression is in the source, from its first token to its last.
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Literal { span, .. }
+            | Expression::Unary { span, .. }
+            | Expression::Group { span, .. }
+            | Expression::Call { span, .. }
+            | Expression::Interpolation { span, .. } => *span,
+            Expression::Binary { lhs, rhs, .. } => Span::new(lhs.span().start, rhs.span().end),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum InterpolationPart {
+    /// Unescaped text; the span covers its source text between the delimiters.
+    Text {
+        value: String,
+        span: Span,
+    },
+    /// An embedded expression; the span runs from its first to its last token.
+    Expr {
+        expr: Expression,
+        span: Span,
     },
-}
\ No newline at end of file
+}
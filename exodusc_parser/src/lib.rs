@@ -2,4 +2,8 @@ pub mod expressions;
 pub mod parser;
 pub mod stantaments;
 pub mod values;
-pub mod errors;
\ No newline at end of file
+pub mod diagnostics;
+pub mod errors;
+
+#[cfg(test)]
+mod tests;
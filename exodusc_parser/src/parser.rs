@@ -1,261 +1,902 @@
 use crate::{
-    errors::SyntaxErrors::{self, *},
-    expressions::Expression,
-    stantaments::Stantament,
+    diagnostics::Diagnostic,
+    errors::{
+        SyntaxErrors::{self, *},
+        SyntaxWarnings::{self, *},
+    },
+    expressions::{Expression, InterpolationPart},
+    stantaments::{MatchArm, Stantament},
 };
-use colored::Colorize;
-use exodusc_lexer::{scanner, types::Type, Lexer};
+use exodusc_lexer::{scanner, source_map::{FileId, SourceMap}, span::Span, symbol::Symbol, token_set::TokenSet, types::Type, Lexer, TokenSet};
 use std::collections::HashMap;
 
 pub type AST = Vec<Stantament>;
 
-pub struct Parser {
-    lexer: Lexer,
+/// Keywords that start a top-level declaration, where parsing resumes after
+/// an error outside any scope. `pub` and `extern` are included so the `func`
+/// after them is not parsed on its own.
+const ITEM_KEYWORDS: [scanner::Keywords; 7] = [
+    scanner::Keywords::Func,
+    scanner::Keywords::Pub,
+    scanner::Keywords::Extern,
+    scanner::Keywords::Struct,
+    scanner::Keywords::Enum,
+    scanner::Keywords::Const,
+    scanner::Keywords::Import,
+];
+
+/// Keywords that start a statement, where parsing resumes after an error
+/// inside a scope.
+const STATEMENT_KEYWORDS: [scanner::Keywords; 10] = [
+    scanner::Keywords::Let,
+    scanner::Keywords::Const,
+    scanner::Keywords::If,
+    scanner::Keywords::While,
+    scanner::Keywords::For,
+    scanner::Keywords::Loop,
+    scanner::Keywords::Match,
+    scanner::Keywords::Break,
+    scanner::Keywords::Continue,
+    scanner::Keywords::Return,
+];
+
+/// Scopes and operands nested deeper than this are rejected instead of
+/// parsed, so that deep nesting is an error and not a stack overflow.
+const MAX_NESTING: usize = 256;
+
+type ParseResult<T> = Result<T, Diagnostic>;
+
+/// `///` lines read but not yet attached to the item after them.
+#[derive(Default)]
+struct PendingDoc {
+    lines: Vec<Span>,
+    span: Span,
+}
+
+impl PendingDoc {
+    fn push(&mut self, line: Span) {
+        if self.lines.is_empty() {
+            self.span.start = line.start;
+        }
+        self.span.end = line.end;
+        self.lines.push(line);
+    }
+}
+
+/// Everything parsing a file produced: the declarations, with an
+/// [`Stantament::Error`] wherever one failed to parse, and every error and
+/// warning found.
+#[derive(Debug, Clone)]
+pub struct ParseOutput {
+    pub ast: AST,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParseOutput {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    /// The AST if there were no errors, otherwise every diagnostic.
+    pub fn into_result(self) -> Result<AST, Vec<Diagnostic>> {
+        match self.has_errors() {
+            true => Err(self.diagnostics),
+            false => Ok(self.ast),
+        }
+    }
+
+    /// Prints every diagnostic to stdout, in source order.
+    pub fn print(&self, map: &SourceMap) {
+        for diagnostic in &self.diagnostics {
+            println!("{}", diagnostic.render(map));
+        }
+    }
+}
+
+pub struct Parser<'src> {
+    file: FileId,
+    lexer: Lexer<'src>,
     ast: AST,
+    diagnostics: Vec<Diagnostic>,
+    /// Scopes and operands currently being parsed, see [`MAX_NESTING`].
+    depth: usize,
 }
 
-impl Parser {
-    pub fn default(lexer: Lexer) -> Self {
+impl<'src> Parser<'src> {
+    /// Parser of the tokens in `lexer`, which are the text of `file`.
+    pub fn default(file: FileId, lexer: Lexer<'src>) -> Self {
         let ast = AST::new();
-        Self { lexer, ast }
+        Self { file, lexer, ast, diagnostics: Vec::new(), depth: 0 }
+    }
+
+    pub fn lexer(&self) -> &Lexer<'src> {
+        &self.lexer
     }
 
-    pub fn analyse(&mut self) -> AST {
+    /// Parses the whole source. Syntax errors are recorded and parsing
+    /// resumes after them; tokens the lexer rejected are skipped, and a
+    /// syntax error they cause is not reported on top of the lexical one.
+    /// Nothing is printed, see [`ParseOutput::print`].
+    pub fn analyse(&mut self) -> ParseOutput {
+        self.analyse_items();
+        let warnings = self.lexer.warnings().to_vec();
+        let source = self.lexer.source();
+        let mut diagnostics: Vec<_> = warnings
+            .iter()
+            .map(|warning| Diagnostic::from_lex_warning(self.file, warning, source))
+            .collect();
+        diagnostics.extend(self.lexer.errors().iter().map(|error| Diagnostic::from_lex_error(self.file, error)));
+        diagnostics.append(&mut self.diagnostics);
+        diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+
+        ParseOutput { ast: std::mem::take(&mut self.ast), diagnostics }
+    }
+
+    fn analyse_items(&mut self) {
+        let mut doc = PendingDoc::default();
         loop {
-            match self.lexer.next() {
-                scanner::Token::Keyword(scanner::Keywords::Func) => self.analyse_func(),
-                scanner::Token::EOF => break,
-                token => self.unexpected(token),
+            let start = self.lexer.peek_span().start;
+            let item = match self.lexer.peek() {
+                scanner::Token::DocComment(scanner::DocComment::Outer) => {
+                    self.lexer.skip_token();
+                    doc.push(self.lexer.span());
+                    continue;
+                }
+                scanner::Token::DocComment(scanner::DocComment::Inner) => {
+                    self.lexer.skip_token();
+                    continue;
+                }
+                scanner::Token::Keyword(scanner::Keywords::Pub) => {
+                    self.lexer.skip_token();
+                    let doc = std::mem::take(&mut doc);
+                    self.analyse_item(doc, true)
+                }
+                scanner::Token::EOF => return self.unattached(doc),
+                _ => {
+                    let doc = std::mem::take(&mut doc);
+                    self.analyse_item(doc, false)
+                }
             };
+            if let Err(error) = item {
+                let error = self.recover_item(error, start);
+                self.ast.push(error);
+            }
+        }
+    }
+
+    /// Parses a top-level declaration. Only functions, structs, enums and
+    /// constants can be `pub` or documented.
+    fn analyse_item(&mut self, doc: PendingDoc, public: bool) -> ParseResult<()> {
+        match self.lexer.next_token() {
+            scanner::Token::Keyword(scanner::Keywords::Func) => self.analyse_func(self.join_doc(&doc), public),
+            scanner::Token::Keyword(scanner::Keywords::Struct) => self.analyse_struct(self.join_doc(&doc), public),
+            scanner::Token::Keyword(scanner::Keywords::Enum) => self.analyse_enum(self.join_doc(&doc), public),
+            scanner::Token::Keyword(scanner::Keywords::Const) => {
+                let constant = self.analyse_const(self.join_doc(&doc), public)?;
+                self.ast.push(constant);
+                Ok(())
+            }
+            scanner::Token::Keyword(keyword @ (scanner::Keywords::Extern | scanner::Keywords::Import)) => {
+                if public {
+                    // Reported at the `pub`; the item itself is still parsed.
+                    let description = format!("`{keyword}` items cannot be `pub`");
+                    let error = Diagnostic::error(self.file, SYNTAX_UNEXPECTED_TOKEN.code(), description, self.lexer.last_span());
+                    self.diagnostics.push(error);
+                }
+                self.unattached(doc);
+                match keyword {
+                    scanner::Keywords::Extern => self.analyse_extern(),
+                    _ => self.analyse_import(),
+                }
+            }
+            token => self.unexpected(token),
         }
-        self.ast.clone()
     }
 
-    fn analyse_func(&mut self) {
+    fn analyse_func(&mut self, doc: Option<String>, public: bool) -> ParseResult<()> {
         let line = self.lexer.line();
+        let name = self.expect_name("func")?;
+        let parms = self.analyse_parms()?;
+        let return_type = self.get_type()?;
+        let body = self.analyse_scope()?;
 
-        let name = match self.lexer.next() {
-            scanner::Token::Identifier(name) => name,
-            _ => self.report(
-                SYNTAX_EXPECTED_NAME,
-                format!("expected `name` after `func`"),
-            ),
-        };
+        self.ast.push(Stantament::Func { name, parms, body, return_type, doc, public, line });
+        Ok(())
+    }
 
-        self.check_token(scanner::Token::LParen);
+    fn analyse_extern(&mut self) -> ParseResult<()> {
+        let line = self.lexer.line();
+        self.check_token(scanner::Token::Keyword(scanner::Keywords::Func))?;
+        let name = self.expect_name("func")?;
+        let parms = self.analyse_parms()?;
+        let return_type = self.get_type()?;
+        self.expect(TokenSet![Semicolon])?;
+
+        self.ast.push(Stantament::Extern { name, parms, return_type, line });
+        Ok(())
+    }
+
+    fn analyse_parms(&mut self) -> ParseResult<HashMap<Symbol, Type>> {
+        self.expect(TokenSet![LParen])?;
 
         let mut parms = HashMap::new();
         loop {
-            let parm = match self.lexer.next() {
+            let parm = match self.lexer.next_token() {
                 scanner::Token::Identifier(parm) => parm,
+                scanner::Token::Keyword(keyword) => self.reserved(keyword)?,
                 scanner::Token::Comma => continue,
                 scanner::Token::RParen => break,
-                token => self.unexpected(token),
+                token => self.expected(TokenSet![Identifier, Comma, RParen], token)?,
             };
 
-            match self.get_type() {
+            match self.get_type()? {
                 Type::Void => {
-                    let description = format!("missing `type`");
-                    self.report(SYNTAX_MISSING_TYPE, description);
+                    let description = "missing `type`".to_string();
+                    return self.report(SYNTAX_MISSING_TYPE, description);
                 }
                 typedef => parms.insert(parm, typedef)
             };
             
 
-            match self.lexer.next() {
-                scanner::Token::Comma => (),
-                scanner::Token::RParen => break,
-                token => self.unexpected(token),
+            if self.expect(TokenSet![Comma, RParen])? == scanner::Token::RParen {
+                break;
+            }
+        }
+        Ok(parms)
+    }
+
+    fn analyse_struct(&mut self, doc: Option<String>, public: bool) -> ParseResult<()> {
+        let line = self.lexer.line();
+        let name = self.expect_name("struct")?;
+        self.expect(TokenSet![LBrace])?;
+
+        let mut fields = Vec::new();
+        loop {
+            let field = match self.lexer.next_token() {
+                scanner::Token::Identifier(field) => field,
+                scanner::Token::Keyword(keyword) => self.reserved(keyword)?,
+                scanner::Token::RBrace => break,
+                token => self.expected(TokenSet![Identifier, RBrace], token)?,
+            };
+
+            match self.get_type()? {
+                Type::Void => {
+                    let description = format!("missing `type` for field `{field}`");
+                    return self.report(SYNTAX_MISSING_TYPE, description);
+                }
+                typedef => fields.push((field, typedef)),
+            };
+
+            if self.expect(TokenSet![Comma, RBrace])? == scanner::Token::RBrace {
+                break;
             }
         }
 
-        let return_type = self.get_type();
-        let body = self.analyse_scope();
+        self.ast.push(Stantament::Struct { name, fields, doc, public, line });
+        Ok(())
+    }
+
+    fn analyse_enum(&mut self, doc: Option<String>, public: bool) -> ParseResult<()> {
+        let line = self.lexer.line();
+        let name = self.expect_name("enum")?;
+        self.expect(TokenSet![LBrace])?;
+
+        let mut variants = Vec::new();
+        loop {
+            match self.lexer.next_token() {
+                scanner::Token::Identifier(variant) => variants.push(variant),
+                scanner::Token::Keyword(keyword) => self.reserved(keyword)?,
+                scanner::Token::RBrace => break,
+                token => self.expected(TokenSet![Identifier, RBrace], token)?,
+            };
 
-        self.ast.push(Stantament::Func { name, parms, body, return_type, line })
+            if self.expect(TokenSet![Comma, RBrace])? == scanner::Token::RBrace {
+                break;
+            }
+        }
+
+        self.ast.push(Stantament::Enum { name, variants, doc, public, line });
+        Ok(())
     }
 
-    fn analyse_scope(&mut self) -> Vec<Stantament> {
-        self.check_token(scanner::Token::LBrace);
+    fn analyse_import(&mut self) -> ParseResult<()> {
+        let line = self.lexer.line();
+        let mut path = vec![self.expect_name("import")?];
+        while self.lexer.peek() == scanner::Token::Operator(scanner::Operator::NAVIGATION) {
+            self.lexer.skip_token();
+            path.push(self.expect_name("::")?);
+        }
+
+        let alias = match self.lexer.peek() {
+            scanner::Token::Keyword(scanner::Keywords::As) => {
+                self.lexer.skip_token();
+                Some(self.expect_name("as")?)
+            }
+            _ => None,
+        };
+        self.expect(TokenSet![Semicolon])?;
+
+        self.ast.push(Stantament::Import { path, alias, line });
+        Ok(())
+    }
+
+    /// Parses `{ statements }`. A statement that fails to parse becomes a
+    /// [`Stantament::Error`] and parsing resumes at the next one.
+    fn analyse_scope(&mut self) -> ParseResult<Vec<Stantament>> {
+        self.nested(Self::analyse_scope_inner)
+    }
+
+    fn analyse_scope_inner(&mut self) -> ParseResult<Vec<Stantament>> {
+        self.expect(TokenSet![LBrace])?;
         let mut body = Vec::new();
+        let mut doc = PendingDoc::default();
         loop {
-            match self.lexer.next() {
-                scanner::Token::Keyword(scanner::Keywords::Let) => body.push(self.analyse_let()),
-                scanner::Token::Keyword(scanner::Keywords::If) => body.push(self.analyse_if_else()),
-                scanner::Token::Keyword(scanner::Keywords::While) => body.push(self.analyse_while()),
-                scanner::Token::Keyword(scanner::Keywords::Return) => body.push(self.analyse_return()),
-                scanner::Token::RBrace => break body,
+            let start = self.lexer.peek_span().start;
+            match self.lexer.peek() {
+                scanner::Token::DocComment(scanner::DocComment::Outer) => {
+                    self.lexer.skip_token();
+                    doc.push(self.lexer.span());
+                    continue;
+                }
+                scanner::Token::Keyword(scanner::Keywords::Const) => (),
+                _ => self.unattached(std::mem::take(&mut doc)),
+            }
+            if let scanner::Token::Identifier(_) | scanner::Token::LParen = self.lexer.peek() {
+                match self.analyse_call() {
+                    Ok(statement) => body.push(statement),
+                    Err(error) => body.push(self.recover_statement(error, start)),
+                }
+                continue;
+            }
+            let statement = match self.lexer.next_token() {
+                scanner::Token::Keyword(scanner::Keywords::Let) => self.analyse_let(),
+                scanner::Token::Keyword(scanner::Keywords::Const) => {
+                    let doc = std::mem::take(&mut doc);
+                    self.analyse_const(self.join_doc(&doc), false)
+                }
+                scanner::Token::Keyword(scanner::Keywords::If) => self.analyse_if_else(),
+                scanner::Token::Keyword(scanner::Keywords::While) => self.analyse_while(),
+                scanner::Token::Keyword(scanner::Keywords::For) => self.analyse_for(),
+                scanner::Token::Keyword(scanner::Keywords::Loop) => self.analyse_loop(),
+                scanner::Token::Keyword(scanner::Keywords::Match) => self.analyse_match(),
+                scanner::Token::Keyword(scanner::Keywords::Break) => self.analyse_break(),
+                scanner::Token::Keyword(scanner::Keywords::Continue) => self.analyse_continue(),
+                scanner::Token::Keyword(scanner::Keywords::Return) => self.analyse_return(),
+                scanner::Token::DocComment(_) => {
+                    let description = "`//!` doc comments are only allowed outside functions".to_string();
+                    self.warn(SYNTAX_UNATTACHED_DOC_COMMENT, description, self.lexer.span());
+                    continue;
+                }
+                scanner::Token::RBrace => break Ok(body),
+                scanner::Token::EOF => return self.expected(TokenSet![RBrace], scanner::Token::EOF),
                 token => self.unexpected(token),
             };
+            match statement {
+                Ok(statement) => body.push(statement),
+                Err(error) => body.push(self.recover_statement(error, start)),
+            }
         }
     }
     
-    fn analyse_let(&mut self) -> Stantament {
-        let name = match self.lexer.next() {
-            scanner::Token::Identifier(name) => name,
-            _ => {
-                let error_msg = format!("expected a name after `let` keyword on line {}", self.lexer.line());
-                self.report(SYNTAX_EXPECTED_NAME, error_msg)
-            }
-        };
+    fn analyse_let(&mut self) -> ParseResult<Stantament> {
+        let mutable = self.lexer.peek() == scanner::Token::Keyword(scanner::Keywords::Mut);
+        if mutable {
+            self.lexer.skip_token();
+        }
+        let name = self.expect_name(if mutable { "mut" } else { "let" })?;
     
-        let typedef = self.get_type();
+        let typedef = self.get_type()?;
     
-        self.check_token(scanner::Token::Operator(scanner::Operator::ASSIGNMENT));
+        self.check_token(scanner::Token::Operator(scanner::Operator::ASSIGNMENT))?;
     
-        let expr = self.parse_exprs(scanner::Token::Semicolon);
+        let expr = self.parse_expr(scanner::Token::Semicolon)?;
         let line = self.lexer.line();
     
-        Stantament::Let {
+        Ok(Stantament::Let {
             name,
             typedef,
             expr,
+            mutable,
             line,
-        }
+        })
+    }
+
+    fn analyse_const(&mut self, doc: Option<String>, public: bool) -> ParseResult<Stantament> {
+        let line = self.lexer.line();
+        let name = self.expect_name("const")?;
+        let typedef = self.get_type()?;
+
+        self.check_token(scanner::Token::Operator(scanner::Operator::ASSIGNMENT))?;
+
+        let expr = self.parse_expr(scanner::Token::Semicolon)?;
+
+        Ok(Stantament::Const { name, typedef, expr, doc, public, line })
     }
 
-    fn analyse_if_else(&mut self) -> Stantament{
+    fn analyse_if_else(&mut self) -> ParseResult<Stantament> {
         let line = self.lexer.line();
-        self.check_token(scanner::Token::LParen);
+        self.expect(TokenSet![LParen])?;
 
-        let condition = self.parse_exprs(scanner::Token::RParen);
+        let condition = self.parse_expr(scanner::Token::RParen)?;
 
-        let then = self.analyse_scope();
+        let then = self.analyse_scope()?;
 
         let or = match self.lexer.peek() {
             scanner::Token::Keyword(scanner::Keywords::Else) => {
-                self.lexer.skip();
-                self.analyse_scope()
+                self.lexer.skip_token();
+                self.analyse_scope()?
             },
             _=> Vec::new()
         };
 
-        Stantament::If { condition, then, or, line }
+        Ok(Stantament::If { condition, then, or, line })
     }
     
-    fn analyse_while(&mut self) -> Stantament {
+    fn analyse_while(&mut self) -> ParseResult<Stantament> {
         let line = self.lexer.line();
-        self.check_token(scanner::Token::LParen);
+        self.expect(TokenSet![LParen])?;
 
-        let condition = self.parse_exprs(scanner::Token::RParen);
+        let condition = self.parse_expr(scanner::Token::RParen)?;
 
-        let body = self.analyse_scope();
+        let body = self.analyse_scope()?;
 
-        Stantament::While { condition, body, line }
+        Ok(Stantament::While { condition, body, line })
     }
 
-    fn analyse_return(&mut self) -> Stantament {
-        let expr = self.parse_exprs(scanner::Token::Semicolon);
+    fn analyse_for(&mut self) -> ParseResult<Stantament> {
+        let line = self.lexer.line();
+        self.expect(TokenSet![LParen])?;
+
+        let item = self.expect_name("(")?;
+        self.check_token(scanner::Token::Keyword(scanner::Keywords::In))?;
+        let iter = self.parse_expr(scanner::Token::RParen)?;
 
-        Stantament::Return { expr }
+        let body = self.analyse_scope()?;
+
+        Ok(Stantament::For { item, iter, body, line })
     }
 
-    fn parse_exprs(&mut self, end: scanner::Token) -> Vec<Expression>{
-        let mut expr = Vec::new();
-        let x = self.parse_value();
-    
-        let mut operator = None;
-        match self.lexer.next() {
-            scanner::Token::Operator(op) => {
-                operator = Some(op);
-            },
-            scanner::Token::Semicolon => {
-                expr.push(Expression::Unary { operator, value: x.unwrap() });
-                return expr;
+    fn analyse_loop(&mut self) -> ParseResult<Stantament> {
+        let line = self.lexer.line();
+        let body = self.analyse_scope()?;
+
+        Ok(Stantament::Loop { body, line })
+    }
+
+    fn analyse_match(&mut self) -> ParseResult<Stantament> {
+        let line = self.lexer.line();
+        self.expect(TokenSet![LParen])?;
+
+        let value = self.parse_expr(scanner::Token::RParen)?;
+
+        self.expect(TokenSet![LBrace])?;
+        let mut arms = Vec::new();
+        loop {
+            match self.lexer.peek() {
+                scanner::Token::RBrace => {
+                    self.lexer.skip_token();
+                    break;
+                }
+                scanner::Token::Comma => {
+                    self.lexer.skip_token();
+                    continue;
+                }
+                _ => (),
+            }
+
+            let fat_arrow = scanner::Token::Operator(scanner::Operator::FAT_ARROW);
+            if self.lexer.peek() == fat_arrow {
+                let description = "expected a pattern before `=>`".to_string();
+                return self.report_at(SYNTAX_EXPECTED_EXPRS, description, self.lexer.peek_span());
+            }
+            let pattern = self.parse_expr(fat_arrow)?;
+            let body = self.analyse_scope()?;
+            arms.push(MatchArm { pattern, body });
+        }
+
+        Ok(Stantament::Match { value, arms, line })
+    }
+
+    fn analyse_break(&mut self) -> ParseResult<Stantament> {
+        let line = self.lexer.line();
+        self.expect(TokenSet![Semicolon])?;
+
+        Ok(Stantament::Break { line })
+    }
+
+    fn analyse_continue(&mut self) -> ParseResult<Stantament> {
+        let line = self.lexer.line();
+        self.expect(TokenSet![Semicolon])?;
+
+        Ok(Stantament::Continue { line })
+    }
+
+    /// Parses `callee(args);`. Other expressions have no effect as a
+    /// statement and are rejected.
+    fn analyse_call(&mut self) -> ParseResult<Stantament> {
+        let expr = self.parse_expression(0)?;
+        if !matches!(expr, Expression::Call { .. }) {
+            let description = "only a function call can be used as a statement".to_string();
+            return self.report_at(SYNTAX_EXPECTED_CALL, description, expr.span());
+        }
+        self.expect(TokenSet![Semicolon])?;
+        let line = self.lexer.location(expr.span().start).0;
+
+        Ok(Stantament::Call { expr, line })
+    }
+
+    fn analyse_return(&mut self) -> ParseResult<Stantament> {
+        let expr = self.parse_expr(scanner::Token::Semicolon)?;
+
+        Ok(Stantament::Return { expr })
+    }
+
+    /// Parses an expression followed by `end`, which is consumed.
+    fn parse_expr(&mut self, end: scanner::Token) -> ParseResult<Expression> {
+        let expr = self.parse_expression(0)?;
+        self.check_token(end)?;
+        Ok(expr)
+    }
+
+    /// Parses an expression by precedence climbing: operands are joined by
+    /// infix operators binding at least as tight as `min_precedence`, per
+    /// [`scanner::Operator::precedence`]. Stops before the first token that
+    /// is not such an operator.
+    fn parse_expression(&mut self, min_precedence: u8) -> ParseResult<Expression> {
+        let mut lhs = self.parse_operand(false)?;
+        loop {
+            let op = match self.lexer.peek() {
+                scanner::Token::Operator(op) => op,
+                _ => return Ok(lhs),
+            };
+            let (precedence, associativity) = match op.precedence() {
+                Some((precedence, associativity)) if precedence >= min_precedence => (precedence, associativity),
+                _ => return Ok(lhs),
+            };
+            self.lexer.skip_token();
+
+            // A left-associative operator only takes tighter operators on its
+            // right, so `a - b - c` is `(a - b) - c`; a right-associative one
+            // takes itself too, so `a = b = c` is `a = (b = c)`.
+            let next = match associativity {
+                scanner::Associativity::Left => precedence + 1,
+                scanner::Associativity::Right => precedence,
+            };
+            let rhs = self.parse_expression(next)?;
+            lhs = Expression::Binary { lhs: Box::new(lhs), op, rhs: Box::new(rhs) };
+        }
+    }
+
+    /// Parses an operand: a value, an interpolated string, a parenthesized
+    /// expression, any of those followed by calls, or a prefix `-`, `!`, `&`
+    /// or `*` applied to an operand. Calls bind tighter than prefix
+    /// operators, and those tighter than any infix one, so `-f(a) * b` is
+    /// `(-(f(a))) * b`. `negated` is set right after a `-`, where a literal
+    /// may reach its type's minimum.
+    fn parse_operand(&mut self, negated: bool) -> ParseResult<Expression> {
+        self.nested(|parser| parser.parse_operand_inner(negated))
+    }
+
+    fn parse_operand_inner(&mut self, negated: bool) -> ParseResult<Expression> {
+        let start = self.lexer.peek_span().start;
+        let mut expr = match self.lexer.peek() {
+            scanner::Token::Interpolation(scanner::StringPart::Head) => self.parse_interpolation()?,
+            scanner::Token::LParen => {
+                self.lexer.skip_token();
+                let expr = self.parse_expression(0)?;
+                self.expect(TokenSet![RParen])?;
+                let span = Span::new(start, self.lexer.span().end);
+                Expression::Group { expr: Box::new(expr), span }
+            }
+            scanner::Token::Operator(
+                op @ (scanner::Operator::SUB
+                | scanner::Operator::NOT
+                | scanner::Operator::ADDRESSING
+                | scanner::Operator::MUL),
+            ) => {
+                self.lexer.skip_token();
+                let operand = self.parse_operand(op == scanner::Operator::SUB)?;
+                let span = Span::new(start, operand.span().end);
+                return Ok(Expression::Unary { op, operand: Box::new(operand), span });
+            }
+            _ => {
+                let value = self.parse_value()?;
+                self.check_literal(&value, negated)?;
+                Expression::Literal { value, span: self.lexer.span() }
             }
-            _ => ()
         };
-    
-        let y = self.parse_value();
-    
-        if x.is_some() && y.is_some() {
-            expr.push(Expression::Binary { x: x.unwrap(), operator: operator.unwrap(), y: y.unwrap() });
-        } else if x.is_none() && y.is_some() {
-            expr.push(Expression::Unary { operator, value: y.unwrap() });
+
+        while self.lexer.peek() == scanner::Token::LParen {
+            let args = self.parse_args()?;
+            let span = Span::new(start, self.lexer.span().end);
+            expr = Expression::Call { callee: Box::new(expr), args, span };
         }
-    
-        if self.lexer.peek() != end {
-            expr.extend(self.parse_exprs(end));
+        Ok(expr)
+    }
+
+    /// Parses `(arg, ...)` after a callee; a trailing comma is allowed.
+    fn parse_args(&mut self) -> ParseResult<Vec<Expression>> {
+        self.expect(TokenSet![LParen])?;
+
+        let mut args = Vec::new();
+        loop {
+            if self.lexer.peek() == scanner::Token::RParen {
+                self.lexer.skip_token();
+                break;
+            }
+            args.push(self.parse_expression(0)?);
+
+            if self.expect(TokenSet![Comma, RParen])? == scanner::Token::RParen {
+                break;
+            }
         }
-        self.lexer.skip();
+        Ok(args)
+    }
 
-        return expr;
+    /// Parses an interpolated string from its head token through its tail.
+    fn parse_interpolation(&mut self) -> ParseResult<Expression> {
+        let is_resume = |token| matches!(
+            token,
+            scanner::Token::Interpolation(scanner::StringPart::Middle | scanner::StringPart::Tail)
+        );
+        let start = self.lexer.peek_span().start;
+        let mut parts = Vec::new();
+        loop {
+            let part = match self.lexer.next_token() {
+                scanner::Token::Interpolation(part) => part,
+                token => self.unexpected(token)?,
+            };
+            let span = self.lexer.span();
+            let value = self.lexer.text(span);
+            if !value.is_empty() {
+                let value = value.into_owned();
+                let span = Span::new(span.start + 1, span.end - 1);
+                parts.push(InterpolationPart::Text { value, span });
+            }
+            if let scanner::StringPart::Tail = part {
+                return Ok(Expression::Interpolation { parts, span: Span::new(start, span.end) });
+            }
+
+            if is_resume(self.lexer.peek()) {
+                self.lexer.skip_token();
+                return self.report(SYNTAX_EXPECTED_EXPRS, "expected an expression between `{` and `}`".to_string());
+            }
+            let expr_start = self.lexer.peek_span().start;
+            let expr = self.parse_expression(0)?;
+            let span = Span::new(expr_start, self.lexer.span().end);
+            parts.push(InterpolationPart::Expr { expr, span });
+        }
     }
-    
-    fn parse_value(&mut self) -> Option<scanner::Literal> {
-        match self.lexer.next() {
-            scanner::Token::Literal(literal) => Some(literal),
-            scanner::Token::Identifier(var) => Some(scanner::Literal::Var(var)),
-            scanner::Token::Operator(_) => {
-                self.lexer.back();
-                None
-            },
+
+    fn parse_value(&mut self) -> ParseResult<scanner::Literal> {
+        let value = match self.lexer.peek() {
+            scanner::Token::Literal(literal) => literal,
+            scanner::Token::Identifier(var) => scanner::Literal::Var(var),
             token => {
                 let expected_value_msg = format!("expected a value before `{}`", token);
-                self.report(SYNTAX_EXPECTED_VALUE, expected_value_msg)
+                return self.report_at(SYNTAX_EXPECTED_VALUE, expected_value_msg, self.lexer.peek_span());
             }
-        }
+        };
+        self.lexer.skip_token();
+        Ok(value)
     }
     
-    fn check_token(&mut self, token: scanner::Token) {
-        if self.lexer.peek() == token{
-            self.lexer.skip();
-            return;
+    /// Checks that a numeric literal fits its type once its sign is applied,
+    /// so `-128i8` is accepted while `128i8` is not.
+    fn check_literal(&self, literal: &scanner::Literal, negated: bool) -> ParseResult<()> {
+        let (fits, typedef) = match literal {
+            scanner::Literal::TypedInteger(value, typedef) => {
+                let limit = typedef.integer_limit(negated).unwrap_or_default();
+                (*value <= limit, *typedef)
+            }
+            scanner::Literal::ULong(value) if negated => (*value <= i64::MIN.unsigned_abs(), Type::I64),
+            _ => return Ok(()),
+        };
+
+        if !fits {
+            let sign = if negated { "-" } else { "" };
+            let description = format!("literal `{sign}{literal}` is out of range for `{typedef}`");
+            return self.report(SYNTAX_LITERAL_OUT_OF_RANGE, description);
+        }
+        Ok(())
+    }
+
+    /// Like [`Parser::expect`], for keywords and operators, whose payload
+    /// matters.
+    fn check_token(&mut self, token: scanner::Token) -> ParseResult<()> {
+        let found = self.lexer.peek();
+        if found == token {
+            self.lexer.skip_token();
+            return Ok(());
+        }
+
+        let description = format!("expected `{token}`, found `{found}`");
+        self.report_at(SYNTAX_EXPECTED_TOKEN, description, self.lexer.peek_span())
+    }
+
+    /// Consumes the next token, reporting every accepted kind if it is not
+    /// one of them. A token that does not match is left for recovery.
+    fn expect(&mut self, expected: TokenSet) -> ParseResult<scanner::Token> {
+        let token = self.lexer.peek();
+        if expected.contains(token.kind()) {
+            self.lexer.skip_token();
+            return Ok(token);
         }
 
-        let back = self.lexer.next();
-        self.report(SYNTAX_EXPECTED_TOKEN,format!("expected `{}` after `{}`. ", token, back));
+        let description = format!("expected {expected}, found `{token}`");
+        self.report_at(SYNTAX_EXPECTED_TOKEN, description, self.lexer.peek_span())
     }
 
-    fn get_type(&mut self) -> Type {
+    fn get_type(&mut self) -> ParseResult<Type> {
         match self.lexer.peek() {
-            scanner::Token::Colon => self.lexer.skip(),
-            _=> return Type::Void
+            scanner::Token::Colon => self.lexer.skip_token(),
+            _=> return Ok(Type::Void)
         }
 
-        match self.lexer.next() {
+        let typedef = match self.lexer.peek() {
             scanner::Token::Type(typed) => typed,
             scanner::Token::Identifier(id) => Type::Object(id),
-            token => self.report(
+            token => return self.report_at(
                 SYNTAX_EXPECTED_TYPE,
                 format!("expected `type` after `:`, found `{}`", token),
+                self.lexer.peek_span(),
+            ),
+        };
+        self.lexer.skip_token();
+        Ok(typedef)
+    }
+
+    fn expect_name(&mut self, after: &str) -> ParseResult<Symbol> {
+        match self.lexer.peek() {
+            scanner::Token::Identifier(name) => {
+                self.lexer.skip_token();
+                Ok(name)
+            }
+            scanner::Token::Keyword(keyword) => {
+                self.lexer.skip_token();
+                self.reserved(keyword)
+            }
+            _ => self.report_at(
+                SYNTAX_EXPECTED_NAME,
+                format!("expected `name` after `{after}`"),
+                self.lexer.peek_span(),
             ),
         }
     }
 
-    fn unexpected(&self, token: scanner::Token) -> ! {
+    fn reserved<T>(&self, keyword: scanner::Keywords) -> ParseResult<T> {
+        self.report(
+            SYNTAX_RESERVED_WORD,
+            format!("`{keyword}` is a reserved word and cannot be used as a name"),
+        )
+    }
+
+    fn unexpected<T>(&self, token: scanner::Token) -> ParseResult<T> {
         self.report(
             SYNTAX_UNEXPECTED_TOKEN, format!("unexpected token `{}`", token),
         )
     }
 
-    fn report(&self, status: SyntaxErrors, description: String) -> ! {
-        let filename = self.lexer.filename();
-        let lines = self.lexer.line();
-        let column_number = self.lexer.column();
-        let max_digits = (lines as f64).log10().floor() as usize + 1;
-        let padded_number = format!("{:>0width$}", lines, width=max_digits);
+    fn expected<T>(&self, expected: TokenSet, found: scanner::Token) -> ParseResult<T> {
+        self.report(SYNTAX_EXPECTED_TOKEN, format!("expected {expected}, found `{found}`"))
+    }
 
-        let source = self.lexer.get_source_line(lines).unwrap();
-        let space = " ".repeat(lines.to_string().len());
+    fn report<T>(&self, status: SyntaxErrors, description: String) -> ParseResult<T> {
+        self.report_at(status, description, self.lexer.span())
+    }
 
-        println!("{space}--> {}:{}:{}",
-                 filename.bold().blue(),
-                 padded_number.bright_white(),
-                 column_number.to_string().bright_white()
-        );
+    fn report_at<T>(&self, status: SyntaxErrors, description: String, span: Span) -> ParseResult<T> {
+        Err(Diagnostic::error(self.file, status.code(), description, span))
+    }
 
+    /// Joins `///` lines into a doc string, dropping the space after the slashes.
+    fn join_doc(&self, doc: &PendingDoc) -> Option<String> {
+        if doc.lines.is_empty() {
+            return None;
+        }
+        let mut text = String::new();
+        for (i, &line) in doc.lines.iter().enumerate() {
+            if i > 0 {
+                text.push('\n');
+            }
+            let line = self.lexer.text(line);
+            text.push_str(line.strip_prefix(' ').unwrap_or(&line).trim_end());
+        }
+        Some(text)
+    }
 
-        println!("{space}|");
-        println!("{number}| {source}", number = padded_number.bright_cyan(), source = source.trim_end().bright_red());
-        println!("{space}|{arrow}\x1b[91m^\x1b[0m", arrow = " ".repeat(column_number));
-        println!("{space}|{arrow}\x1b[91m|\x1b[0m", arrow = " ".repeat(column_number));
-        println!("{space}| [{}]\x1b[91m error: {}\x1b[0m",  status.code().bright_red().bold(), description.bright_red());
-        std::process::exit(1);
+    /// Warns about `///` lines with no item after them to document.
+    fn unattached(&mut self, doc: PendingDoc) {
+        if doc.lines.is_empty() {
+            return;
+        }
+        let description = "doc comment is not followed by a function, struct, enum or constant".to_string();
+        self.warn(SYNTAX_UNATTACHED_DOC_COMMENT, description, doc.span);
     }
-    
+
+    fn warn(&mut self, status: SyntaxWarnings, description: String, span: Span) {
+        self.diagnostics.push(Diagnostic::warning(self.file, status.code(), description, span));
+    }
+
+    /// Runs `parse` one level deeper, failing at the next token instead once
+    /// [`MAX_NESTING`] levels are open.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.depth == MAX_NESTING {
+            let description = format!("nesting is too deep, at most {MAX_NESTING} levels are allowed");
+            return self.report_at(SYNTAX_NESTING_TOO_DEEP, description, self.lexer.peek_span());
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    /// Records `error`, unless a lexical error between the last token
+    /// consumed and it explains it: `let c = '';` is reported once, as an
+    /// empty character literal, not again as a missing value.
+    ///
+    /// Every scope still open at the end of the source fails there; only the
+    /// innermost is reported, and the ones around it close quietly.
+    fn record(&mut self, error: Diagnostic) {
+        let end = self.lexer.source().len();
+        if error.span.start == end && self.diagnostics.last().is_some_and(|last| last.span.start == end) {
+            return;
+        }
+        let errors = self.lexer.errors_so_far();
+        let last = self.lexer.span().end;
+        let first = errors.partition_point(|lexical| lexical.span.start < last);
+        if errors.get(first).is_none_or(|lexical| lexical.span.start > error.span.start) {
+            self.diagnostics.push(error);
+        }
+    }
+
+    /// Records `error` and skips the rest of a failed top-level declaration,
+    /// up to the next keyword outside braces that can start one.
+    fn recover_item(&mut self, error: Diagnostic, start: usize) -> Stantament {
+        self.record(error);
+        let mut depth = 0usize;
+        loop {
+            match self.lexer.peek() {
+                scanner::Token::EOF => break,
+                scanner::Token::Keyword(keyword) if depth == 0 && ITEM_KEYWORDS.contains(&keyword) => break,
+                scanner::Token::LBrace => depth += 1,
+                scanner::Token::RBrace => depth = depth.saturating_sub(1),
+                _ => (),
+            }
+            self.lexer.skip_token();
+        }
+        Stantament::Error { span: Span::new(start, self.lexer.span().end.max(start)) }
+    }
+
+    /// Records `error` and skips the rest of a failed statement: past the
+    /// next `;`, or up to the `}` closing the scope or the next statement
+    /// keyword. Nested blocks are skipped whole, so nothing in them is
+    /// reported again.
+    fn recover_statement(&mut self, error: Diagnostic, start: usize) -> Stantament {
+        self.record(error);
+        let mut depth = 0usize;
+        loop {
+            match self.lexer.peek() {
+                scanner::Token::EOF => break,
+                scanner::Token::RBrace if depth == 0 => break,
+                scanner::Token::Keyword(keyword) if depth == 0 && STATEMENT_KEYWORDS.contains(&keyword) => break,
+                scanner::Token::Semicolon if depth == 0 => {
+                    self.lexer.skip_token();
+                    break;
+                }
+                scanner::Token::LBrace => depth += 1,
+                scanner::Token::RBrace => depth -= 1,
+                _ => (),
+            }
+            self.lexer.skip_token();
+        }
+        Stantament::Error { span: Span::new(start, self.lexer.span().end.max(start)) }
+    }
+
     pub fn reset(&mut self) {
         self.lexer.reset();
         self.ast.clear();
     }
 }
+
+
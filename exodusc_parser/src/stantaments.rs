@@ -1,38 +1,127 @@
 use crate::expressions::Expression;
-use exodusc_lexer::types::Type;
+use exodusc_lexer::{span::Span, symbol::Symbol, types::Type};
 use std::collections::HashMap;
 
-#[derive(Clone, Debug)]
+#[derive(PartialEq, Clone, Debug)]
 pub enum Stantament {
     Let {
-        name: String,
+        name: Symbol,
         typedef: Type,
-        expr: Vec<Expression>,
+        expr: Expression,
+        mutable: bool,
+        line: usize,
+    },
+
+    Const {
+        name: Symbol,
+        typedef: Type,
+        expr: Expression,
+        doc: Option<String>,
+        public: bool,
         line: usize,
     },
 
     If {
-        condition: Vec<Expression>,
+        condition: Expression,
         then: Vec<Stantament>,
         or: Vec<Stantament>,
         line: usize,
     },
 
     Func {
-        name: String,
-        parms: HashMap<String, Type>,
+        name: Symbol,
+        parms: HashMap<Symbol, Type>,
         body: Vec<Stantament>,
         return_type: Type,
+        doc: Option<String>,
+        public: bool,
+        line: usize,
+    },
+
+    /// `extern func name(parms): type;`, a function defined outside Exodus.
+    Extern {
+        name: Symbol,
+        parms: HashMap<Symbol, Type>,
+        return_type: Type,
+        line: usize,
+    },
+
+    Struct {
+        name: Symbol,
+        fields: Vec<(Symbol, Type)>,
+        doc: Option<String>,
+        public: bool,
+        line: usize,
+    },
+
+    Enum {
+        name: Symbol,
+        variants: Vec<Symbol>,
+        doc: Option<String>,
+        public: bool,
+        line: usize,
+    },
+
+    /// `import a::b::c as d;`
+    Import {
+        path: Vec<Symbol>,
+        alias: Option<Symbol>,
         line: usize,
     },
 
     While{
-        condition: Vec<Expression>,
+        condition: Expression,
         body: Vec<Stantament>,
         line: usize
     },
 
+    /// `for (item in iter) { ... }`
+    For {
+        item: Symbol,
+        iter: Expression,
+        body: Vec<Stantament>,
+        line: usize,
+    },
+
+    Loop {
+        body: Vec<Stantament>,
+        line: usize,
+    },
+
+    Break {
+        line: usize,
+    },
+
+    Continue {
+        line: usize,
+    },
+
+    Match {
+        value: Expression,
+        arms: Vec<MatchArm>,
+        line: usize,
+    },
+
+    /// `callee(args);`, a call made for its effect.
+    Call {
+        expr: Expression,
+        line: usize,
+    },
+
     Return {
-        expr: Vec<Expression>
+        expr: Expression
+    },
+
+    /// A declaration or statement that failed to parse, up to where parsing
+    /// resumed. Its diagnostic is in [`crate::parser::ParseOutput`].
+    Error {
+        span: Span,
     },
 }
+
+/// `pattern => { body }` inside a `match`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct MatchArm {
+    pub pattern: Expression,
+    pub body: Vec<Stantament>,
+}
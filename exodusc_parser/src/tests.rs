@@ -0,0 +1,554 @@
+use crate::{
+    diagnostics::{Diagnostic, Severity},
+    expressions::{Expression, InterpolationPart},
+    parser::{ParseOutput, Parser},
+    stantaments::Stantament,
+};
+use exodusc_lexer::{source_map::SourceMap, span::Span, symbol::Symbol, types::Type, Lexer};
+use std::collections::HashMap;
+
+fn parse(source: &str) -> ParseOutput {
+    let file = SourceMap::new().add("main", source);
+    Parser::default(file, Lexer::tokenization("main", source)).analyse()
+}
+
+fn codes(output: &ParseOutput) -> Vec<&str> {
+    output.diagnostics.iter().map(|diagnostic| diagnostic.code).collect()
+}
+
+#[test]
+fn analyse_valid_source() {
+    let source = "func main(): i32 {\n    let x = 1 + 2;\n    return x;\n}\nstruct Point { x: i32, y: i32 }\n";
+
+    let output = parse(source);
+    assert!(output.diagnostics.is_empty());
+    assert!(!output.has_errors());
+    assert_eq!(output.ast.len(), 2);
+    assert!(matches!(output.ast[0], Stantament::Func { .. }));
+    assert_eq!(output.into_result().map(|ast| ast.len()), Ok(2));
+}
+
+#[test]
+fn analyse_syntax_error() {
+    let source = "func f(a: i32 b: i32) {}\n";
+
+    let mut map = SourceMap::new();
+    let file = map.add("main", source);
+    let output = Parser::default(file, Lexer::tokenization("main", source)).analyse();
+    assert_eq!(
+        output.diagnostics,
+        [Diagnostic::error(file, "E0101", "expected one of: `,`, `)`, found `b`".to_string(), Span::new(14, 15))]
+    );
+    assert_eq!(output.ast, [Stantament::Error { span: Span::new(0, 24) }]);
+    assert!(output.into_result().is_err());
+}
+
+#[test]
+fn analyse_keeps_items_around_an_error() {
+    let source = "enum A { X }\nfunc main() { let = 1; let y = 2; }\n";
+
+    let output = parse(source);
+    assert_eq!(output.ast.len(), 2);
+    assert_eq!(codes(&output), ["E0100"]);
+    assert_eq!(output.diagnostics[0].message, "expected `name` after `let`");
+    let Stantament::Func { body, .. } = &output.ast[1] else { panic!("expected a function") };
+    assert_eq!(body.len(), 2);
+    assert_eq!(body[0], Stantament::Error { span: Span::new(27, 35) });
+    assert!(matches!(body[1], Stantament::Let { .. }));
+}
+
+#[test]
+fn recover_every_statement_error() {
+    let source = "func main() {\n    let = 1;\n    let x: = 2;\n    let y = 3;\n    break\n}\n";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0100", "E0102", "E0101"]);
+    assert_eq!(output.diagnostics[2].message, "expected `;`, found `}`");
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    assert!(matches!(
+        body[..],
+        [Stantament::Error { .. }, Stantament::Error { .. }, Stantament::Let { .. }, Stantament::Error { .. }]
+    ));
+}
+
+#[test]
+fn recover_skips_nested_blocks() {
+    let source = "func main() {\n    if (x +) {\n        let = 1;\n    }\n    return x;\n}\n";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0106"]);
+    assert_eq!(output.diagnostics[0].message, "expected a value before `)`");
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    assert!(matches!(body[..], [Stantament::Error { .. }, Stantament::Return { .. }]));
+}
+
+#[test]
+fn recover_at_the_next_item() {
+    let source = "func f(a b) { let x = 1; }\nstruct S { a: }\npub func g() {}\nextern func h();\n";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0104", "E0102"]);
+    assert!(matches!(
+        output.ast[..],
+        [Stantament::Error { .. }, Stantament::Error { .. }, Stantament::Func { .. }, Stantament::Extern { .. }]
+    ));
+}
+
+#[test]
+fn recover_from_pub_on_extern_and_import() {
+    let output = parse("pub extern func x();\npub import a::b;\nfunc main() {}\n");
+    assert_eq!(codes(&output), ["E0103", "E0103"]);
+    assert_eq!(output.diagnostics[0].message, "`extern` items cannot be `pub`");
+    assert_eq!(output.diagnostics[0].span, Span::new(0, 3));
+    assert_eq!(output.diagnostics[1].message, "`import` items cannot be `pub`");
+    assert!(matches!(
+        output.ast[..],
+        [Stantament::Extern { .. }, Stantament::Import { .. }, Stantament::Func { .. }]
+    ));
+}
+
+#[test]
+fn recover_from_an_unclosed_scope() {
+    let output = parse("func main() {\n    let x = 1;\n");
+    assert_eq!(codes(&output), ["E0101"]);
+    assert_eq!(output.diagnostics[0].message, "expected `}`, found `end of file`");
+    assert_eq!(output.ast.len(), 1);
+
+    let output = parse("func f() { while (x) { if (y) {");
+    assert_eq!(codes(&output), ["E0101"]);
+    assert_eq!(output.diagnostics[0].span, Span::new(31, 31));
+    assert_eq!(output.ast, [Stantament::Error { span: Span::new(0, 31) }]);
+}
+
+/// The statements in the body of `func main() { <source> }`.
+fn parse_body(source: &str) -> Vec<Stantament> {
+    let output = parse(&format!("func main() {{ {source} }}"));
+    assert!(output.diagnostics.is_empty(), "{:?}", output.diagnostics);
+    match output.ast.into_iter().next() {
+        Some(Stantament::Func { body, .. }) => body,
+        item => panic!("expected a function, found {item:?}"),
+    }
+}
+
+fn names(names: &[&str]) -> Vec<Symbol> {
+    names.iter().map(|name| Symbol::intern(name)).collect()
+}
+
+#[test]
+fn analyse_doc_comments() {
+    let source = "//! The module.\n/// a\n/// b\nfunc f() {}\n//! Not f's.\nfunc g() {}\n///  Indented. \npub struct S { x: i32 }\n/// E\nenum E { A }\n";
+
+    let output = parse(source);
+    assert!(output.diagnostics.is_empty());
+    let docs: Vec<_> = output
+        .ast
+        .iter()
+        .map(|item| match item {
+            Stantament::Func { doc, .. } | Stantament::Struct { doc, .. } | Stantament::Enum { doc, .. } => doc.as_deref(),
+            item => panic!("unexpected item {item:?}"),
+        })
+        .collect();
+    assert_eq!(docs, [Some("a\nb"), None, Some(" Indented."), Some("E")]);
+}
+
+#[test]
+fn analyse_doc_comments_on_constants() {
+    let source = "/// N\npub const N = 1;\nfunc f() {\n    /// M\n    const M = 2;\n}\n";
+
+    let output = parse(source);
+    assert!(output.diagnostics.is_empty());
+    assert!(matches!(&output.ast[0], Stantament::Const { doc: Some(doc), .. } if doc == "N"));
+    let Stantament::Func { body, .. } = &output.ast[1] else { panic!("expected a function") };
+    assert!(matches!(&body[0], Stantament::Const { doc: Some(doc), .. } if doc == "M"));
+}
+
+#[test]
+fn warn_about_unattached_doc_comments() {
+    let source = "/// a\nimport io;\nfunc f() {\n    /// b\n    /// c\n    let x = 1;\n    //! d\n    /// e\n}\n/// f\n";
+
+    let output = parse(source);
+    assert!(!output.has_errors());
+    assert_eq!(codes(&output), ["W0100", "W0100", "W0100", "W0100", "W0100"]);
+    let spans: Vec<_> = output.diagnostics.iter().map(|diagnostic| &source[diagnostic.span.start..diagnostic.span.end]).collect();
+    assert_eq!(spans, ["/// a", "/// b\n    /// c", "//! d", "/// e", "/// f"]);
+    assert!(matches!(output.ast[..], [Stantament::Import { .. }, Stantament::Func { .. }]));
+}
+
+#[test]
+fn analyse_imports() {
+    let output = parse("import a::b as c;\nimport io;\n");
+    assert!(output.diagnostics.is_empty());
+    assert_eq!(
+        output.ast,
+        [
+            Stantament::Import { path: names(&["a", "b"]), alias: Some(Symbol::intern("c")), line: 1 },
+            Stantament::Import { path: names(&["io"]), alias: None, line: 2 },
+        ]
+    );
+}
+
+#[test]
+fn analyse_extern() {
+    let output = parse("extern func puts(s: string, n: i32): i32;\nextern func exit();\n");
+    assert!(output.diagnostics.is_empty());
+    let parms = HashMap::from([(Symbol::intern("s"), Type::String), (Symbol::intern("n"), Type::I32)]);
+    assert_eq!(
+        output.ast,
+        [
+            Stantament::Extern { name: Symbol::intern("puts"), parms, return_type: Type::I32, line: 1 },
+            Stantament::Extern { name: Symbol::intern("exit"), parms: HashMap::new(), return_type: Type::Void, line: 2 },
+        ]
+    );
+}
+
+#[test]
+fn analyse_public_items() {
+    let source = "pub const N: i32 = 1;\nconst M = 2;\npub struct P { x: i32, y: Point }\nenum E { A, B, }\npub func f() {}\n";
+
+    let output = parse(source);
+    assert!(output.diagnostics.is_empty());
+    let [constant, private, structure, enumeration, func] = &output.ast[..] else { panic!("expected five items") };
+    let Stantament::Const { name, typedef: Type::I32, expr, doc: None, public: true, line: 1 } = constant else {
+        panic!("expected `pub const N`, found {constant:?}")
+    };
+    assert_eq!((*name, show(expr)), (Symbol::intern("N"), "1".to_string()));
+    assert!(matches!(private, Stantament::Const { typedef: Type::Void, public: false, line: 2, .. }));
+    assert_eq!(
+        *structure,
+        Stantament::Struct {
+            name: Symbol::intern("P"),
+            fields: vec![(Symbol::intern("x"), Type::I32), (Symbol::intern("y"), Type::Object(Symbol::intern("Point")))],
+            doc: None,
+            public: true,
+            line: 3,
+        }
+    );
+    assert_eq!(
+        *enumeration,
+        Stantament::Enum { name: Symbol::intern("E"), variants: names(&["A", "B"]), doc: None, public: false, line: 4 }
+    );
+    assert!(matches!(func, Stantament::Func { public: true, line: 5, .. }));
+}
+
+#[test]
+fn analyse_let() {
+    let body = parse_body("let mut x: i32 = 1; let y = x;");
+    let [mutable, immutable] = &body[..] else { panic!("expected two `let`s, found {body:?}") };
+    let Stantament::Let { name, typedef: Type::I32, expr, mutable: true, .. } = mutable else {
+        panic!("expected `let mut x: i32`, found {mutable:?}")
+    };
+    assert_eq!((*name, show(expr)), (Symbol::intern("x"), "1".to_string()));
+    assert!(matches!(immutable, Stantament::Let { typedef: Type::Void, mutable: false, .. }));
+}
+
+#[test]
+fn analyse_loops() {
+    let body = parse_body("for (i in 0..n) { break; } while (i < n) { continue; } loop { break; }");
+    let [
+        Stantament::For { item, iter, body: for_body, .. },
+        Stantament::While { condition, body: while_body, .. },
+        Stantament::Loop { body: loop_body, .. },
+    ] = &body[..]
+    else {
+        panic!("expected `for`, `while` and `loop`, found {body:?}")
+    };
+    assert_eq!((*item, show(iter)), (Symbol::intern("i"), "(0 .. n)".to_string()));
+    assert_eq!(show(condition), "(i < n)");
+    assert!(matches!(for_body[..], [Stantament::Break { .. }]));
+    assert!(matches!(while_body[..], [Stantament::Continue { .. }]));
+    assert!(matches!(loop_body[..], [Stantament::Break { .. }]));
+}
+
+#[test]
+fn analyse_match() {
+    let body = parse_body("match (x + 1) { 1 => {}, _ => { return x; } }");
+    let [Stantament::Match { value, arms, .. }] = &body[..] else { panic!("expected a `match`, found {body:?}") };
+    assert_eq!(show(value), "(x + 1)");
+    let arms: Vec<_> = arms.iter().map(|arm| (show(&arm.pattern), arm.body.len())).collect();
+    assert_eq!(arms, [("1".to_string(), 0), ("_".to_string(), 1)]);
+}
+
+#[test]
+fn analyse_reserved_names() {
+    let output = parse("func f(let: i32) {}\nstruct S { if: i32 }\nenum E { A, match }\nfunc while() {}\n");
+    assert_eq!(codes(&output), ["E0108", "E0108", "E0108", "E0108"]);
+    let messages: Vec<_> = output.diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "`let` is a reserved word and cannot be used as a name",
+            "`if` is a reserved word and cannot be used as a name",
+            "`match` is a reserved word and cannot be used as a name",
+            "`while` is a reserved word and cannot be used as a name",
+        ]
+    );
+    assert_eq!(output.diagnostics[0].span, Span::new(7, 10));
+}
+
+/// The expression of `let _ = <source>;`.
+fn parse_expression(source: &str) -> Expression {
+    let output = parse(&format!("func main() {{ let _ = {source}; }}"));
+    assert!(output.diagnostics.is_empty(), "{:?}", output.diagnostics);
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    let Stantament::Let { expr, .. } = &body[0] else { panic!("expected a `let`") };
+    expr.clone()
+}
+
+/// `expr` with every binary and prefix operation parenthesized and groups
+/// left out.
+fn show(expr: &Expression) -> String {
+    match expr {
+        Expression::Literal { value, .. } => value.to_string(),
+        Expression::Unary { op, operand, .. } => format!("({op}{})", show(operand)),
+        Expression::Group { expr, .. } => show(expr),
+        Expression::Call { callee, args, .. } => {
+            let args: Vec<_> = args.iter().map(show).collect();
+            format!("{}({})", show(callee), args.join(", "))
+        }
+        Expression::Binary { lhs, op, rhs } => format!("({} {op} {})", show(lhs), show(rhs)),
+        Expression::Interpolation { .. } => "\"..\"".to_string(),
+    }
+}
+
+/// The expression of `let _ = <source>;`, shown as by [`show`].
+fn expression(source: &str) -> String {
+    show(&parse_expression(source))
+}
+
+#[test]
+fn expression_precedence() {
+    assert_eq!(expression("a + b * c - d"), "((a + (b * c)) - d)");
+    assert_eq!(expression("a * b + c * d"), "((a * b) + (c * d))");
+    assert_eq!(expression("a || b && c == d + 1"), "(a || (b && (c == (d + 1))))");
+    assert_eq!(expression("a | b ^ c & d << 2"), "(a | (b ^ (c & (d << 2))))");
+    assert_eq!(expression("0..n + 1"), "(0 .. (n + 1))");
+    assert_eq!(expression("-1 * x"), "((-1) * x)");
+}
+
+#[test]
+fn expression_associativity() {
+    assert_eq!(expression("a - b - c"), "((a - b) - c)");
+    assert_eq!(expression("a / b * c % d"), "(((a / b) * c) % d)");
+    assert_eq!(expression("a = b += c"), "(a = (b += c))");
+}
+
+#[test]
+fn expression_groups() {
+    assert_eq!(expression("(a + b) * c"), "((a + b) * c)");
+    assert_eq!(expression("a - (b - c)"), "(a - (b - c))");
+    assert_eq!(expression("((a))"), "a");
+
+    let expr = parse_expression("(a + b) * c");
+    let Expression::Binary { lhs, .. } = &expr else { panic!("expected a binary expression") };
+    assert!(matches!(**lhs, Expression::Group { .. }));
+    assert_eq!(lhs.span(), Span::new(22, 29));
+    assert_eq!(expr.span(), Span::new(22, 33));
+}
+
+#[test]
+fn expression_prefix_operators() {
+    assert_eq!(expression("!done"), "(!done)");
+    assert_eq!(expression("&value"), "(&value)");
+    assert_eq!(expression("*ptr + 1"), "((*ptr) + 1)");
+    assert_eq!(expression("-a * -b"), "((-a) * (-b))");
+    assert_eq!(expression("!(-x > 0)"), "(!((-x) > 0))");
+    assert_eq!(expression("*&*p"), "(*(&(*p)))");
+
+    let expr = parse_expression("!(-x > 0)");
+    assert_eq!(expr.span(), Span::new(22, 31));
+    let Expression::Unary { operand, .. } = &expr else { panic!("expected a prefix operator") };
+    let Expression::Group { expr, .. } = &**operand else { panic!("expected a group") };
+    let Expression::Binary { lhs, .. } = &**expr else { panic!("expected a binary expression") };
+    assert_eq!(lhs.span(), Span::new(24, 26));
+}
+
+#[test]
+fn expression_calls() {
+    assert_eq!(expression("foo(1, 2)"), "foo(1, 2)");
+    assert_eq!(expression("f()"), "f()");
+    assert_eq!(expression("f(a,)"), "f(a)");
+    assert_eq!(expression("f(g(x))(y)"), "f(g(x))(y)");
+    assert_eq!(expression("a + f(b) * 2"), "(a + (f(b) * 2))");
+    assert_eq!(expression("-f(x)"), "(-f(x))");
+    assert_eq!(expression("f(a + b, !c)"), "f((a + b), (!c))");
+    assert_eq!(expression("(f)(x)"), "f(x)");
+
+    let expr = parse_expression("f(g(x))(y)");
+    assert_eq!(expr.span(), Span::new(22, 32));
+    let Expression::Call { callee, args, .. } = &expr else { panic!("expected a call") };
+    assert_eq!(callee.span(), Span::new(22, 29));
+    assert_eq!(args.len(), 1);
+}
+
+#[test]
+fn call_statements() {
+    let source = "func main() {\n    print(1, 2);\n    f(g(x))(y);\n    return f(1);\n}\n";
+
+    let output = parse(source);
+    assert!(output.diagnostics.is_empty(), "{:?}", output.diagnostics);
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    assert!(matches!(
+        body[..],
+        [Stantament::Call { line: 2, .. }, Stantament::Call { line: 3, .. }, Stantament::Return { .. }]
+    ));
+    let Stantament::Call { expr: Expression::Call { callee, args, span }, .. } = &body[0] else {
+        panic!("expected a call")
+    };
+    assert!(matches!(**callee, Expression::Literal { .. }));
+    assert_eq!(args.len(), 2);
+    assert_eq!(*span, Span::new(18, 29));
+}
+
+#[test]
+fn call_statement_errors() {
+    let source = "func main() {\n    f(1 2);\n    x + 1;\n    g(1)\n    h();\n}\n";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0101", "E0109", "E0101"]);
+    assert_eq!(output.diagnostics[0].message, "expected one of: `,`, `)`, found `2`");
+    assert_eq!(output.diagnostics[1].message, "only a function call can be used as a statement");
+    assert_eq!(output.diagnostics[1].span, Span::new(30, 35));
+    assert_eq!(output.diagnostics[2].message, "expected `;`, found `h`");
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    assert_eq!(body.len(), 3);
+    assert!(body.iter().all(|statement| matches!(statement, Stantament::Error { .. })));
+}
+
+#[test]
+fn expression_interpolation() {
+    let expr = parse_expression("\"user {name} has {count + 1} items\"");
+    let Expression::Interpolation { parts, span } = &expr else { panic!("expected an interpolated string") };
+    assert_eq!(*span, Span::new(22, 57));
+    let parts: Vec<_> = parts
+        .iter()
+        .map(|part| match part {
+            InterpolationPart::Text { value, span } => (value.clone(), *span),
+            InterpolationPart::Expr { expr, span } => (format!("{{{}}}", show(expr)), *span),
+        })
+        .collect();
+    assert_eq!(
+        parts,
+        [
+            ("user ".to_string(), Span::new(23, 28)),
+            ("{name}".to_string(), Span::new(29, 33)),
+            (" has ".to_string(), Span::new(34, 39)),
+            ("{(count + 1)}".to_string(), Span::new(40, 49)),
+            (" items".to_string(), Span::new(50, 56)),
+        ]
+    );
+}
+
+#[test]
+fn expression_interpolation_errors() {
+    let output = parse("func main() { let a = \"x {} y\"; let b = \"{1 +}\"; }");
+    assert_eq!(codes(&output), ["E0105", "E0106"]);
+    assert_eq!(output.diagnostics[0].message, "expected an expression between `{` and `}`");
+    assert_eq!(output.diagnostics[1].message, "expected a value before `}…\"`");
+}
+
+#[test]
+fn expression_errors() {
+    let output = parse("func main() { let x = 1 +; let y = a b; let z = (a + b; return 2 * -128i8; }");
+    assert_eq!(codes(&output), ["E0106", "E0101", "E0101"]);
+    assert_eq!(output.diagnostics[0].message, "expected a value before `;`");
+    assert_eq!(output.diagnostics[1].message, "expected `;`, found `b`");
+    assert_eq!(output.diagnostics[2].message, "expected `)`, found `;`");
+}
+
+#[test]
+fn analyse_literal_out_of_range() {
+    let output = parse("func main() { let x = 128i8; let y = -128i8; }");
+    assert_eq!(codes(&output), ["E0107"]);
+    assert_eq!(output.diagnostics[0].message, "literal `128i8` is out of range for `i8`");
+}
+
+#[test]
+fn analyse_lexical_errors() {
+    let source = "func main() { let s = \"\\q\"; let c = ''; }";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0003", "E0006"]);
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    assert!(matches!(body[..], [Stantament::Let { .. }, Stantament::Error { .. }]));
+}
+
+#[test]
+fn analyse_past_lexical_errors() {
+    let source = "func main() { let x = $; let y = 1 $ 2; }\nfunc f(a) {}\nfunc g() { let = 1; }\n";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0001", "E0001", "E0104", "E0100"]);
+    assert_eq!(output.ast.len(), 3);
+}
+
+#[test]
+fn report_syntax_errors_apart_from_lexical_ones() {
+    let source = "func f() { let c = $; let d = 1;";
+
+    let output = parse(source);
+    assert_eq!(codes(&output), ["E0001", "E0101"]);
+    assert_eq!(output.diagnostics[1].message, "expected `}`, found `end of file`");
+    assert_eq!(output.diagnostics[1].span, Span::new(32, 32));
+}
+
+#[test]
+fn reject_deep_nesting() {
+    let nested = |open: &str, inner: &str, close: &str, depth| open.repeat(depth) + inner + &close.repeat(depth);
+
+    let source = format!("func main() {{ let x = {}; let y = 1; }}", nested("(", "1", ")", 3000));
+    let output = parse(&source);
+    assert_eq!(codes(&output), ["E0110"]);
+    assert_eq!(output.diagnostics[0].message, "nesting is too deep, at most 256 levels are allowed");
+    let Stantament::Func { body, .. } = &output.ast[0] else { panic!("expected a function") };
+    assert!(matches!(body[..], [Stantament::Error { .. }, Stantament::Let { .. }]));
+
+    let source = format!("func main() {{ let x = {}; }}", nested("-", "1", "", 3000));
+    assert_eq!(codes(&parse(&source)), ["E0110"]);
+
+    let source = format!("func main() {}\nfunc f() {{}}", nested("{ loop ", "{}", "}", 3000));
+    let output = parse(&source);
+    assert_eq!(codes(&output), ["E0110"]);
+    assert!(matches!(output.ast[..], [Stantament::Func { .. }, Stantament::Func { .. }]));
+
+    let source = format!("func main() {{ let x = {}; }}", nested("(", "1", ")", 250));
+    assert!(parse(&source).diagnostics.is_empty());
+    let source = format!("func main() {}", nested("{ loop ", "{}", "}", 250));
+    assert!(parse(&source).diagnostics.is_empty());
+}
+
+#[test]
+fn analyse_warnings_are_not_errors() {
+    let source = "func main() {\n    let раураl = 1;\n}\n";
+
+    let output = parse(source);
+    assert!(!output.has_errors());
+    assert_eq!(output.diagnostics.len(), 1);
+    assert_eq!(output.diagnostics[0].severity, Severity::Warning);
+    assert_eq!(output.diagnostics[0].code, "W0002");
+    assert!(output.diagnostics[0].message.ends_with("`раураl` looks like `paypal`"));
+    assert_eq!(output.into_result().map(|ast| ast.len()), Ok(1));
+}
+
+#[test]
+fn render_diagnostic() {
+    colored::control::set_override(false);
+    let mut map = SourceMap::new();
+    let lib = map.add("lib.ex", "func f() {\n    return;\n}\n");
+    let main = map.add("main.ex", "func main() {\n\tlet = 1;\n}\n");
+
+    let output = Parser::default(main, Lexer::tokenization("main.ex", map.get(main).source())).analyse();
+    assert_eq!(output.diagnostics[0].file, main);
+    let rendered = output.diagnostics[0].render(&map);
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines[0], " --> main.ex:2:5");
+    assert_eq!(lines[2], "2|     let = 1;");
+    assert!(lines[3].ends_with("     \x1b[91m^\x1b[0m"));
+    assert!(lines[5].contains("error: expected `name` after `let`"));
+
+    let output = Parser::default(lib, Lexer::tokenization("lib.ex", map.get(lib).source())).analyse();
+    let rendered = output.diagnostics[0].render(&map);
+    let lines: Vec<_> = rendered.lines().collect();
+    assert_eq!(lines[0], " --> lib.ex:2:10");
+    assert_eq!(lines[2], "2|     return;");
+    assert!(lines[5].contains("error: expected a value before `;`"));
+}